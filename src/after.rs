@@ -0,0 +1,95 @@
+// `cron_after(last_run, pattern, n)` emits the next `n` occurrences strictly
+// after a job's last run.
+
+use chrono::{DateTime, Local, TimeDelta};
+use chrono_tz::Tz;
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, search_limit,
+    timezone_parameter,
+};
+use crate::stream::{emit_occurrences, OccurrenceBindData, OccurrenceInitData};
+
+pub(crate) struct AfterVTab;
+
+impl VTab for AfterVTab {
+    type InitData = OccurrenceInitData;
+    type BindData = OccurrenceBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut OccurrenceBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).init();
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 1)?)?;
+        let timezone = timezone_parameter(bind)?;
+        // A job that never ran is scheduled from now.
+        let last_run = bind.get_parameter(0);
+        let after: DateTime<Tz> = match last_run.to_string().as_str() {
+            "NULL" => Local::now().with_timezone(&timezone),
+            _ => datetime_from_micros(last_run.to_int64_timestamp(), &timezone)
+                .ok_or("Invalid last run time")?,
+        };
+        let n = bind.get_parameter(2).to_int64();
+        if n < 0 {
+            return Err(format!(
+                "The number of occurrences must not be negative, found {}",
+                n
+            )
+            .into());
+        }
+
+        // Occurrences fall on whole seconds, so the first one after the last
+        // run is at least a second later. The bounds are inclusive, the last
+        // one is the nth occurrence.
+        (*data).start = after + TimeDelta::seconds(1);
+        let limit = search_limit(after);
+        (*data).until = match n {
+            0 => after,
+            _ => cron
+                .iter_after(after)
+                .take_while(|&x| x <= limit)
+                .take(n as usize)
+                .last()
+                .unwrap_or(after),
+        };
+
+        (*data).set_pattern(cron);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OccurrenceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).init();
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OccurrenceInitData>();
+        let bind_info = func.get_bind_data::<OccurrenceBindData>();
+        emit_occurrences(bind_info, init_info, output)
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Bigint),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,96 @@
+// `cron_age(pattern, ts)` returns how long it has been since a schedule last
+// fired, for alerting on stale jobs.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_interval;
+
+use crate::schedule::{
+    datetime_from_micros, floor_occurrence, parse_pattern_or_error, pattern_parameter,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct AgeBindData {
+    age: Option<duckdb_interval>,
+}
+
+impl Free for AgeBindData {}
+
+// Converts a duration into an INTERVAL of whole days and microseconds, the
+// way DuckDB represents the difference of two timestamps.
+pub(crate) fn to_interval(duration: TimeDelta) -> duckdb_interval {
+    let days = duration.num_days();
+    let rest = duration - TimeDelta::days(days);
+    duckdb_interval {
+        months: 0,
+        days: days as i32,
+        micros: rest.num_microseconds().unwrap_or(0),
+    }
+}
+
+// The time since the last occurrence at or before `at`, None if there is no
+// occurrence within the search horizon.
+pub(crate) fn age(cron: &Cron, at: DateTime<Tz>) -> Option<TimeDelta> {
+    floor_occurrence(cron, at).map(|previous| at - previous)
+}
+
+pub(crate) struct AgeVTab;
+
+impl VTab for AgeVTab {
+    type InitData = OnceInitData;
+    type BindData = AgeBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut AgeBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("age", LogicalType::new(LogicalTypeId::Interval));
+        (*data).age = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let at = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid timestamp")?;
+
+        (*data).age = age(&cron, at).map(to_interval);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<AgeBindData>();
+        emit_once(init_info, output, (*bind_info).age);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
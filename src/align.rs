@@ -0,0 +1,118 @@
+// The period boundaries `cron()` can align its start to, so iteration begins
+// at the start of the period containing it rather than at an arbitrary instant.
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Alignment {
+    Minute,
+    Hour,
+    Day,
+    Month,
+}
+
+impl Alignment {
+    pub(crate) fn parse(alignment: &str) -> Result<Self, String> {
+        match alignment {
+            "minute" => Ok(Alignment::Minute),
+            "hour" => Ok(Alignment::Hour),
+            "day" => Ok(Alignment::Day),
+            "month" => Ok(Alignment::Month),
+            _ => Err(format!(
+                "Invalid align_to '{}', expected 'minute', 'hour', 'day' or 'month'",
+                alignment
+            )),
+        }
+    }
+
+    // Truncates an instant down to the start of the period containing it on
+    // the local wall clock of its zone.
+    //
+    // A day can start after midnight when a daylight saving transition skips
+    // it, the day then starts at the first local time that exists.
+    pub(crate) fn truncate(&self, at: DateTime<Tz>) -> DateTime<Tz> {
+        let into_hour = TimeDelta::seconds(i64::from(at.minute() * 60 + at.second()));
+        let day = match self {
+            Alignment::Minute => return at - TimeDelta::seconds(i64::from(at.second())),
+            Alignment::Hour => return at - into_hour,
+            Alignment::Day => at.date_naive(),
+            Alignment::Month => at.date_naive().with_day(1).unwrap_or(at.date_naive()),
+        };
+        start_of_day(&at.timezone(), day)
+            .filter(|&start| start <= at)
+            .unwrap_or(at)
+    }
+}
+
+// The first instant of a local date in a zone, which is after midnight when a
+// daylight saving transition skips it.
+pub(crate) fn start_of_day(timezone: &Tz, day: NaiveDate) -> Option<DateTime<Tz>> {
+    (0..24 * 60).find_map(|minute| {
+        timezone
+            .with_ymd_and_hms(
+                day.year(),
+                day.month(),
+                day.day(),
+                minute / 60,
+                minute % 60,
+                0,
+            )
+            .earliest()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::{New_York, Sao_Paulo};
+
+    // An instant given in UTC, in a zone.
+    fn utc(zone: Tz, (y, m, d): (i32, u32, u32), hour: u32, minute: u32) -> DateTime<Tz> {
+        Tz::UTC
+            .with_ymd_and_hms(y, m, d, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&zone)
+    }
+
+    #[test]
+    fn spring_forward_days_are_short() {
+        let at = |hour, minute| utc(New_York, (2024, 3, 10), hour, minute);
+        // 03:30 EDT, the day began at midnight EST.
+        assert_eq!(Alignment::Day.truncate(at(7, 30)), at(5, 0));
+        assert_eq!(Alignment::Hour.truncate(at(7, 30)), at(7, 0));
+        // 01:45 EST.
+        assert_eq!(Alignment::Hour.truncate(at(6, 45)), at(6, 0));
+    }
+
+    #[test]
+    fn fall_back_hours_repeat() {
+        let at = |hour, minute| utc(New_York, (2024, 11, 3), hour, minute);
+        // The first 01:30, EDT, and the second, EST, truncate to their own
+        // 01:00.
+        assert_eq!(Alignment::Hour.truncate(at(5, 30)), at(5, 0));
+        assert_eq!(Alignment::Hour.truncate(at(6, 30)), at(6, 0));
+        // Both are in the day that began at midnight EDT.
+        assert_eq!(Alignment::Day.truncate(at(5, 30)), at(4, 0));
+        assert_eq!(Alignment::Day.truncate(at(6, 30)), at(4, 0));
+        // 23:00 EST is 25 hours into the day.
+        assert_eq!(
+            Alignment::Day.truncate(utc(New_York, (2024, 11, 4), 4, 0)),
+            at(4, 0)
+        );
+    }
+
+    #[test]
+    fn days_skipping_midnight_start_after_it() {
+        // Clocks in São Paulo jumped from midnight to 01:00 on 2018-11-04.
+        let noon = utc(Sao_Paulo, (2018, 11, 4), 14, 0);
+        assert_eq!(
+            Alignment::Day.truncate(noon),
+            utc(Sao_Paulo, (2018, 11, 4), 3, 0)
+        );
+        assert_eq!(
+            Alignment::Month.truncate(noon),
+            utc(Sao_Paulo, (2018, 11, 1), 3, 0)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+// `cron_align_series(pattern, timestamps)` snaps a batch of times down to the
+// occurrence of a schedule they fall in, parsing the pattern once for the
+// whole batch when bucketing large event sets.
+
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use std::ptr::null_mut;
+
+use crate::params::{parse_list, parse_timestamp_micros};
+use crate::schedule::{
+    datetime_from_micros, floor_occurrence, parse_pattern_or_error, pattern_parameter,
+    timezone_parameter,
+};
+use crate::single_row::OnceInitData;
+
+#[repr(C)]
+pub(crate) struct AlignSeriesBindData {
+    // The occurrence each input falls in, None for NULL inputs and inputs
+    // without an occurrence before them within the search horizon.
+    floors: *mut Vec<Option<i64>>,
+}
+
+impl Free for AlignSeriesBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.floors.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.floors));
+        }
+    }
+}
+
+// Floors each of DuckDB's renderings of a TIMESTAMP to the last occurrence
+// at or before it, in seconds since the epoch.
+fn align_series(
+    cron: &Cron,
+    timestamps: &[String],
+    timezone: &chrono_tz::Tz,
+) -> Result<Vec<Option<i64>>, String> {
+    timestamps
+        .iter()
+        .map(|text| {
+            if text == "NULL" {
+                return Ok(None);
+            }
+            let at = datetime_from_micros(parse_timestamp_micros(text)?, timezone)
+                .ok_or_else(|| format!("Invalid timestamp: {}", text))?;
+            Ok(floor_occurrence(cron, at).map(|x| x.timestamp()))
+        })
+        .collect()
+}
+
+pub(crate) struct AlignSeriesVTab;
+
+impl VTab for AlignSeriesVTab {
+    type InitData = OnceInitData;
+    type BindData = AlignSeriesBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut AlignSeriesBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).floors = null_mut();
+        bind.add_result_column(
+            "aligned",
+            LogicalType::list(&LogicalType::new(LogicalTypeId::TimestampS)),
+        );
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let timestamps = parse_list(&bind.get_parameter(1).to_string())?;
+        let floors = align_series(&cron, &timestamps, &timezone)?;
+        (*data).floors = Box::into_raw(Box::new(floors));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<AlignSeriesBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        let floors = &*(*bind_info).floors;
+        let mut aligned = output.list_vector(0);
+        let values: Vec<i64> = floors.iter().map(|x| x.unwrap_or(0)).collect();
+        aligned.set_child(&values);
+        let mut child = aligned.child(floors.len());
+        for (i, floor) in floors.iter().enumerate() {
+            if floor.is_none() {
+                child.set_null(i);
+            }
+        }
+        aligned.set_entry(0, 0, floors.len());
+        output.set_len(1);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Timestamp)),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
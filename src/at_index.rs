@@ -0,0 +1,89 @@
+// `cron_at_index(pattern, start, n)` returns the occurrence with index `n`
+// counting from zero at `start`.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, search_limit,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct AtIndexBindData {
+    occurrence: Option<i64>,
+}
+
+impl Free for AtIndexBindData {}
+
+// The occurrence at index `n` at or after `start`, None if the pattern fires
+// fewer times than that within the search horizon.
+pub(crate) fn at_index(cron: &Cron, start: DateTime<Tz>, n: usize) -> Option<DateTime<Tz>> {
+    let limit = search_limit(start);
+    cron.iter_from(start).take_while(|&x| x <= limit).nth(n)
+}
+
+pub(crate) struct AtIndexVTab;
+
+impl VTab for AtIndexVTab {
+    type InitData = OnceInitData;
+    type BindData = AtIndexBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut AtIndexBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).occurrence = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let n = bind.get_parameter(2).to_int64();
+        if n < 0 {
+            return Err(format!("The index must not be negative, found {}", n).into());
+        }
+
+        (*data).occurrence = at_index(&cron, start, n as usize).map(|x| x.timestamp());
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<AtIndexBindData>();
+        emit_once(init_info, output, (*bind_info).occurrence);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Bigint),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
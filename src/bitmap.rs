@@ -0,0 +1,150 @@
+// `cron_to_bitmap(pattern, start, until, resolution)` packs a schedule's
+// activity over a window into a BLOB with one bit per slot, for compact
+// storage and bitwise comparison of schedules.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_interval;
+use std::ptr::null_mut;
+
+use crate::age::to_interval;
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, pattern_parameter,
+    timezone_parameter,
+};
+use crate::single_row::OnceInitData;
+
+// The most slots a bitmap may have, two MiB of bits.
+const MAX_SLOTS: i64 = 1 << 24;
+
+#[repr(C)]
+pub(crate) struct BitmapBindData {
+    bitmap: *mut Vec<u8>,
+    slots: i64,
+    resolution: duckdb_interval,
+}
+
+impl Free for BitmapBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.bitmap.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.bitmap));
+        }
+    }
+}
+
+// Sets bit `i` of the bitmap, least significant bit of each byte first, when
+// the pattern fires in the `i`th slot of `resolution_seconds` from `start`.
+pub(crate) fn bitmap(
+    cron: &Cron,
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    resolution_seconds: i64,
+    slots: usize,
+) -> Vec<u8> {
+    let mut bits = vec![0u8; slots.div_ceil(8)];
+    for x in occurrences_between(cron, start, until) {
+        let slot = ((x - start).num_seconds() / resolution_seconds) as usize;
+        bits[slot / 8] |= 1 << (slot % 8);
+    }
+    bits
+}
+
+pub(crate) struct BitmapVTab;
+
+impl VTab for BitmapVTab {
+    type InitData = OnceInitData;
+    type BindData = BitmapBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut BitmapBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).bitmap = null_mut();
+        bind.add_result_column("bitmap", LogicalType::new(LogicalTypeId::Blob));
+        bind.add_result_column("slots", LogicalType::new(LogicalTypeId::Bigint));
+        bind.add_result_column("resolution", LogicalType::new(LogicalTypeId::Interval));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        let resolution = parse_interval(&bind.get_parameter(3).to_string())?;
+        if resolution < TimeDelta::seconds(1) {
+            return Err("The resolution must be at least one second".into());
+        }
+        let resolution_seconds = resolution.num_seconds();
+
+        // The last slot may be cut short by `until`.
+        let span = (until - start).num_seconds().max(0);
+        let slots = (span + resolution_seconds - 1) / resolution_seconds;
+        if slots > MAX_SLOTS {
+            return Err(format!(
+                "The bitmap would have {} slots, at most {} are allowed, \
+                 use a coarser resolution or a shorter range",
+                slots, MAX_SLOTS
+            )
+            .into());
+        }
+
+        let bits = bitmap(&cron, start, until, resolution_seconds, slots as usize);
+        (*data).bitmap = Box::into_raw(Box::new(bits));
+        (*data).slots = slots;
+        (*data).resolution = to_interval(TimeDelta::seconds(resolution_seconds));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<BitmapBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        output
+            .flat_vector(0)
+            .insert(0, (*(*bind_info).bitmap).as_slice());
+        output.flat_vector(1).as_mut_slice::<i64>()[0] = (*bind_info).slots;
+        output.flat_vector(2).as_mut_slice::<duckdb_interval>()[0] = (*bind_info).resolution;
+        output.set_len(1);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Interval),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,135 @@
+// `cron_next_business_day(pattern, from, holidays)` finds the next occurrence
+// that lands on a business day, i.e. neither a weekend nor a holiday.
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Weekday};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::params::parse_list;
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, search_limit,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct BusinessDayBindData {
+    next: Option<i64>,
+}
+
+impl Free for BusinessDayBindData {}
+
+pub(crate) fn is_business_day(date: NaiveDate, holidays: &[NaiveDate]) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !holidays.contains(&date)
+}
+
+// Parses DuckDB's rendering of a LIST(DATE).
+pub(crate) fn parse_date_list(text: &str) -> Result<Vec<NaiveDate>, String> {
+    parse_list(text)?
+        .iter()
+        .filter(|x| x.as_str() != "NULL")
+        .map(|x| {
+            NaiveDate::parse_from_str(x, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", x))
+        })
+        .collect()
+}
+
+// Finds the first occurrence after `from` on a business day. When
+// `roll_forward` is set an occurrence on a blocked day is moved to the same
+// local time on the next business day instead of being skipped.
+pub(crate) fn next_business_day(
+    cron: &Cron,
+    from: DateTime<Tz>,
+    holidays: &[NaiveDate],
+    roll_forward: bool,
+) -> Option<DateTime<Tz>> {
+    let limit = search_limit(from);
+    for x in cron.iter_after(from).take_while(|&x| x <= limit) {
+        let date = x.date_naive();
+        if is_business_day(date, holidays) {
+            return Some(x);
+        }
+        if roll_forward {
+            let date = date
+                .iter_days()
+                .skip(1)
+                .find(|&date| is_business_day(date, holidays))?;
+            return x
+                .timezone()
+                .from_local_datetime(&date.and_time(x.time()))
+                .earliest();
+        }
+    }
+    None
+}
+
+pub(crate) struct BusinessDayVTab;
+
+impl VTab for BusinessDayVTab {
+    type InitData = OnceInitData;
+    type BindData = BusinessDayBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut BusinessDayBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).next = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let from = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid from time")?;
+        let holidays = parse_date_list(&bind.get_parameter(2).to_string())?;
+        let roll_forward = match bind.get_named_parameter("roll_forward") {
+            Some(value) => value.to_string() == "true",
+            None => false,
+        };
+
+        (*data).next =
+            next_business_day(&cron, from, &holidays, roll_forward).map(|x| x.timestamp());
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<BusinessDayBindData>();
+        emit_once(init_info, output, (*bind_info).next);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Date)),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![
+            (
+                "roll_forward".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "timezone".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+        ])
+    }
+}
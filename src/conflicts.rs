@@ -0,0 +1,170 @@
+// `cron_conflicts_batch(patterns, start, until)` finds every pair of a list of
+// schedules that fire at the same instant within a window, for contention
+// analysis when onboarding many jobs at once.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::cmp::Ordering;
+use std::ptr::null_mut;
+
+use crate::params::parse_list;
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern, search_limit, timezone_parameter,
+};
+
+#[repr(C)]
+pub(crate) struct ConflictsBindData {
+    // The positions of both patterns and their number of coincident fires.
+    conflicts: *mut Vec<(i32, i32, i64)>,
+}
+
+impl Free for ConflictsBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.conflicts.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.conflicts));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct ConflictsInitData {
+    // The index of the next conflict to emit.
+    cursor: usize,
+}
+
+impl Free for ConflictsInitData {}
+
+// Counts the instants present in both of two ascending lists.
+fn count_coincident(a: &[i64], b: &[i64]) -> i64 {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+// Compares every pair of the schedules' occurrences, generated once each.
+// Pairs whose occurrences don't overlap in time are skipped without merging.
+pub(crate) fn conflicts(occurrences: &[Vec<i64>]) -> Vec<(i32, i32, i64)> {
+    let mut conflicts = Vec::new();
+    for (i, a) in occurrences.iter().enumerate() {
+        for (j, b) in occurrences.iter().enumerate().skip(i + 1) {
+            let disjoint = match (a.first(), a.last(), b.first(), b.last()) {
+                (Some(a_first), Some(a_last), Some(b_first), Some(b_last)) => {
+                    a_last < b_first || b_last < a_first
+                }
+                _ => true,
+            };
+            if disjoint {
+                continue;
+            }
+            let count = count_coincident(a, b);
+            if count > 0 {
+                conflicts.push((i as i32, j as i32, count));
+            }
+        }
+    }
+    conflicts
+}
+
+pub(crate) struct ConflictsVTab;
+
+impl VTab for ConflictsVTab {
+    type InitData = ConflictsInitData;
+    type BindData = ConflictsBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut ConflictsBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).conflicts = null_mut();
+        bind.add_result_column("i", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("j", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("conflicts", LogicalType::new(LogicalTypeId::Bigint));
+
+        let patterns = parse_list(&bind.get_parameter(0).to_string())?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        // The occurrences of every pattern are held in memory.
+        if until > search_limit(start) {
+            return Err("The range of cron_conflicts_batch may span at most five years".into());
+        }
+
+        let mut occurrences = Vec::with_capacity(patterns.len());
+        for (i, pattern) in patterns.iter().enumerate() {
+            let cron = parse_pattern(pattern).map_err(|err| {
+                format!("Failed to parse cron expression at position {}: {}", i, err)
+            })?;
+            occurrences.push(
+                occurrences_between(&cron, start, until)
+                    .map(|x| x.timestamp())
+                    .collect::<Vec<i64>>(),
+            );
+        }
+
+        (*data).conflicts = Box::into_raw(Box::new(conflicts(&occurrences)));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut ConflictsInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).cursor = 0;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<ConflictsInitData>();
+        let bind_info = func.get_bind_data::<ConflictsBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let conflicts = &*(*bind_info).conflicts;
+        let first = (*init_info).cursor.min(conflicts.len());
+        let chunk = &conflicts[first..(first + max_items).min(conflicts.len())];
+
+        let firsts: Vec<i32> = chunk.iter().map(|&(i, _, _)| i).collect();
+        let seconds: Vec<i32> = chunk.iter().map(|&(_, j, _)| j).collect();
+        let counts: Vec<i64> = chunk.iter().map(|&(_, _, count)| count).collect();
+        output.flat_vector(0).copy(&firsts);
+        output.flat_vector(1).copy(&seconds);
+        output.flat_vector(2).copy(&counts);
+        output.set_len(chunk.len());
+        (*init_info).cursor = first + chunk.len();
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Varchar)),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
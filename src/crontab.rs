@@ -0,0 +1,221 @@
+// `cron_crontab(text, start, until)` evaluates the contents of a crontab
+// file, interleaving the occurrences of all of its entries.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_combining, timezone_parameter, DayCombination,
+};
+use crate::timeline::{next_tagged_chunk, TimelineInitData};
+
+#[repr(C)]
+pub(crate) struct CrontabBindData {
+    line_numbers: *mut Vec<i32>,
+    patterns: *mut Vec<Cron>,
+    start: DateTime<Tz>,
+    // Exclusive.
+    until: DateTime<Tz>,
+}
+
+impl Free for CrontabBindData {
+    fn free(&mut self) {
+        unsafe {
+            if !self.line_numbers.is_null() {
+                drop(Box::from_raw(self.line_numbers));
+            }
+            if !self.patterns.is_null() {
+                drop(Box::from_raw(self.patterns));
+            }
+        }
+    }
+}
+
+// Whether a name is a valid environment variable name like `MAILTO`.
+pub(crate) fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()))
+}
+
+// Extracts the schedule of a crontab line, ignoring the command after it.
+// Blank lines, comments, environment assignments like `MAILTO=ops` or
+// `MAILTO = ops` and `@reboot` entries, which have no schedule, give `None`.
+fn entry_pattern(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    // An entry's command may contain `=`, but its schedule never makes the
+    // text before it a variable name.
+    if let Some((name, _)) = line.split_once('=') {
+        if is_variable_name(name.trim()) {
+            return None;
+        }
+    }
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields[0].starts_with('@') {
+        return match fields[0].to_lowercase().as_str() {
+            "@reboot" => None,
+            _ => Some(fields[0].to_string()),
+        };
+    }
+    Some(fields[..fields.len().min(5)].join(" "))
+}
+
+// Parses the entries of a crontab into their line numbers, counted from 1,
+// and patterns.
+fn parse_crontab(text: &str) -> Result<(Vec<i32>, Vec<Cron>), String> {
+    let mut line_numbers = Vec::new();
+    let mut patterns = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let pattern = match entry_pattern(line) {
+            Some(pattern) => pattern,
+            None => continue,
+        };
+        let line_no = i32::try_from(i + 1).map_err(|_| "The crontab has too many lines")?;
+        // Unix cron fires when either restricted day field matches.
+        patterns.push(
+            parse_pattern_combining(&pattern, DayCombination::Or).map_err(|err| {
+                format!(
+                    "Failed to parse cron expression on line {}: {}",
+                    line_no, err
+                )
+            })?,
+        );
+        line_numbers.push(line_no);
+    }
+    Ok((line_numbers, patterns))
+}
+
+pub(crate) struct CrontabVTab;
+
+impl VTab for CrontabVTab {
+    type InitData = TimelineInitData;
+    type BindData = CrontabBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut CrontabBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).line_numbers = null_mut();
+        (*data).patterns = null_mut();
+        bind.add_result_column("line_no", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+
+        let (line_numbers, patterns) = parse_crontab(&bind.get_parameter(0).to_string())?;
+        let timezone = timezone_parameter(bind)?;
+        (*data).start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        (*data).until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        (*data).line_numbers = Box::into_raw(Box::new(line_numbers));
+        (*data).patterns = Box::into_raw(Box::new(patterns));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut TimelineInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).init();
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<TimelineInitData>();
+        let bind_info = func.get_bind_data::<CrontabBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let line_numbers = &*(*bind_info).line_numbers;
+        let rows = next_tagged_chunk(
+            &*(*bind_info).patterns,
+            (*bind_info).start,
+            (*bind_info).until,
+            init_info,
+            max_items,
+        );
+
+        let lines: Vec<i32> = rows.iter().map(|&(i, _)| line_numbers[i]).collect();
+        output.flat_vector(0).copy(&lines);
+        let fires: Vec<i64> = rows.iter().map(|(_, x)| x.timestamp()).collect();
+        output.flat_vector(1).copy(&fires);
+        output.set_len(rows.len());
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn skips_lines_without_schedules() {
+        for line in [
+            "",
+            "   ",
+            "# 0 0 * * * comment",
+            "MAILTO=ops",
+            "MAILTO = ops",
+            "  PATH=/usr/bin:/bin",
+            "CRON_TZ=UTC",
+            "@reboot /usr/bin/start",
+        ] {
+            assert_eq!(entry_pattern(line), None, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn extracts_schedules() {
+        assert_eq!(
+            entry_pattern("*/5 * * * * /usr/bin/run --level=2").as_deref(),
+            Some("*/5 * * * *")
+        );
+        assert_eq!(
+            entry_pattern("0 0 * * * FOO=bar /usr/bin/run").as_deref(),
+            Some("0 0 * * *")
+        );
+        assert_eq!(
+            entry_pattern("@daily /usr/bin/report").as_deref(),
+            Some("@daily")
+        );
+    }
+
+    #[test]
+    fn day_fields_are_ored() {
+        let (line_numbers, patterns) = parse_crontab("MAILTO = ops\n0 0 13 * 5 cmd").unwrap();
+        assert_eq!(line_numbers, vec![2]);
+        // Thursday 2024-06-13 is the 13th and Friday 2024-06-14 a Friday.
+        let day = |d| Tz::UTC.with_ymd_and_hms(2024, 6, d, 0, 0, 0).unwrap();
+        assert!(patterns[0].is_time_matching(&day(13)).unwrap());
+        assert!(patterns[0].is_time_matching(&day(14)).unwrap());
+        assert!(!patterns[0].is_time_matching(&day(12)).unwrap());
+    }
+}
@@ -0,0 +1,209 @@
+// `cron_to_crontab_line(pattern, command)` formats a pattern and a command as
+// an entry of a crontab file, for generating deployable crontabs.
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::crontab::is_variable_name;
+use crate::fields::parse_fields;
+use crate::normalize::normalize;
+use crate::params::parse_list;
+use crate::schedule::pattern_parameter;
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+// Checks an environment assignment like `MAILTO=ops`, which crontab reads
+// from a line of its own.
+fn check_assignment(assignment: &str) -> Result<(), String> {
+    let (name, value) = assignment.split_once('=').ok_or_else(|| {
+        format!(
+            "Invalid environment assignment '{}', expected NAME=value",
+            assignment
+        )
+    })?;
+    if !is_variable_name(name) {
+        return Err(format!("Invalid environment variable name '{}'", name));
+    }
+    if value.contains(['\n', '\r']) {
+        return Err(format!("The value of {} must fit on one line", name));
+    }
+    Ok(())
+}
+
+// Escapes every `%` of a command not already preceded by a backslash.
+fn escape_percent(command: &str) -> String {
+    let mut escaped = String::with_capacity(command.len());
+    let mut previous = None;
+    for c in command.chars() {
+        if c == '%' && previous != Some('\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        previous = Some(c);
+    }
+    escaped
+}
+
+// Formats a crontab entry running `command` on the schedule of `pattern`,
+// after any environment assignments, each on its own line.
+//
+// The pattern is normalized and must not need a seconds field, which crontab
+// lacks, nor use the L, W and # extensions, nor restrict both day fields, which crontab combines with OR rather
+// than AND. crontab turns an unescaped `%` in a command into a newline, so those
+// are escaped, and the command itself must fit on one line.
+pub(crate) fn crontab_line(
+    pattern: &str,
+    command: &str,
+    assignments: &[String],
+) -> Result<String, String> {
+    let schedule = normalize(pattern)?;
+    if schedule.split_whitespace().count() > 5 {
+        return Err(format!(
+            "The cron expression '{}' fires at seconds other than 0, which crontab cannot express",
+            pattern
+        ));
+    }
+    let fields = parse_fields(pattern)?;
+    if fields.iter().any(|field| !field.specials.is_empty()) {
+        return Err(format!(
+            "The cron expression '{}' uses L, W or # items, which crontab cannot express",
+            pattern
+        ));
+    }
+    if !fields[3].wildcard && !fields[5].wildcard {
+        return Err(format!(
+            "The cron expression '{}' restricts both the day of month and the day of week, \
+             which crontab fires on when either matches rather than both",
+            pattern
+        ));
+    }
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("The command must not be empty".to_string());
+    }
+    if command.contains(['\n', '\r']) {
+        return Err("The command must fit on one line".to_string());
+    }
+
+    let mut lines = Vec::new();
+    for assignment in assignments {
+        check_assignment(assignment)?;
+        lines.push(assignment.clone());
+    }
+    lines.push(format!("{} {}", schedule, escape_percent(command)));
+    Ok(lines.join("\n"))
+}
+
+pub(crate) struct CrontabLineVTab;
+
+impl VTab for CrontabLineVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("line", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let pattern = pattern_parameter(bind, 0)?;
+        let command = bind.get_parameter(1).to_string();
+        if command == "NULL" {
+            return Ok(());
+        }
+        let assignments = match bind.get_named_parameter("env") {
+            Some(value) => parse_list(&value.to_string())?,
+            None => Vec::new(),
+        };
+        (*data).set(Some(crontab_line(&pattern, &command, &assignments)?));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "env".to_string(),
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Varchar)),
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(pattern: &str, command: &str) -> Result<String, String> {
+        crontab_line(pattern, command, &[])
+    }
+
+    #[test]
+    fn formats_entries() {
+        assert_eq!(
+            line("0 9 * * MON-FRI", " /usr/bin/backup ").unwrap(),
+            "0 9 * * 1-5 /usr/bin/backup"
+        );
+        assert_eq!(line("@daily", "report").unwrap(), "0 0 * * * report");
+        assert_eq!(
+            crontab_line("0 0 1 * *", "run", &["MAILTO=ops@example.com".to_string()]).unwrap(),
+            "MAILTO=ops@example.com\n0 0 1 * * run"
+        );
+    }
+
+    #[test]
+    fn escapes_percent_signs() {
+        assert_eq!(
+            line("0 0 * * *", "date +%F").unwrap(),
+            "0 0 * * * date +\\%F"
+        );
+        assert_eq!(
+            line("0 0 * * *", "date +\\%F").unwrap(),
+            "0 0 * * * date +\\%F"
+        );
+    }
+
+    #[test]
+    fn rejects_what_crontab_cant_express() {
+        assert!(line("30 0 9 * * *", "run").is_err());
+        assert!(line("0 0 13 * 5", "run").is_err());
+        assert!(line("0 0 L * 1", "run").is_err());
+        assert!(line("0 0 L * *", "run").is_err());
+        assert!(line("0 0 15W * *", "run").is_err());
+        assert!(line("0 0 * * 5#2", "run").is_err());
+        assert!(line("0 0 * * 5L", "run").is_err());
+        assert!(line("0 0 * * *", "  ").is_err());
+        assert!(line("0 0 * * *", "a\nb").is_err());
+    }
+
+    #[test]
+    fn checks_assignments() {
+        assert!(check_assignment("MAILTO=ops").is_ok());
+        assert!(check_assignment("_X1=").is_ok());
+        assert!(check_assignment("MAILTO").is_err());
+        assert!(check_assignment("1X=y").is_err());
+        assert!(check_assignment("MY VAR=y").is_err());
+        assert!(check_assignment("X=a\nb").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+// Recognition of dense patterns that fire on a fixed interval.
+//
+// Patterns like `* * * * *` or `*/5 * * * *` only constrain the minute (or
+// second) field with a step that evenly divides the hour (or minute), so every
+// occurrence is exactly a fixed number of seconds after the previous one.
+// For those patterns the generator can advance by addition instead of asking
+// croner to match every field at every step.
+
+// Returns the step of a `*` or `*/n` field when it evenly divides 60.
+fn field_step(field: &str) -> Option<i64> {
+    let step = match field {
+        "*" => 1,
+        _ => field.strip_prefix("*/")?.parse::<i64>().ok()?,
+    };
+    if step > 0 && 60 % step == 0 {
+        Some(step)
+    } else {
+        None
+    }
+}
+
+// Returns the number of seconds between consecutive occurrences if the
+// pattern fires on a fixed interval, otherwise None.
+pub(crate) fn fixed_interval_seconds(pattern: &str) -> Option<i64> {
+    let fields: Vec<&str> = pattern.split_whitespace().collect();
+    let (seconds, minutes, rest) = match fields.len() {
+        5 => ("0", fields[0], &fields[1..]),
+        6 => (fields[0], fields[1], &fields[2..]),
+        _ => return None,
+    };
+
+    // Hour, day of month, month and day of week must be unconstrained.
+    if rest.iter().any(|field| *field != "*") {
+        return None;
+    }
+
+    if minutes == "*" && seconds == "0" {
+        // Once a minute, like `* * * * *`.
+        Some(60)
+    } else if minutes == "*" {
+        field_step(seconds)
+    } else if seconds == "0" {
+        field_step(minutes).map(|step| step * 60)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::parse_pattern;
+    use chrono::{DateTime, TimeDelta, TimeZone};
+    use chrono_tz::Tz;
+
+    fn start() -> DateTime<Tz> {
+        Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    // The occurrences the fast path produces: the first one, then one every
+    // fixed interval.
+    fn fast(pattern: &str, count: usize) -> Vec<DateTime<Tz>> {
+        let step = TimeDelta::seconds(fixed_interval_seconds(pattern).unwrap());
+        let first = parse_pattern(pattern)
+            .unwrap()
+            .iter_from(start())
+            .next()
+            .unwrap();
+        std::iter::successors(Some(first), |&x| Some(x + step))
+            .take(count)
+            .collect()
+    }
+
+    fn generic(pattern: &str, count: usize) -> Vec<DateTime<Tz>> {
+        parse_pattern(pattern)
+            .unwrap()
+            .iter_from(start())
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn recognizes_fixed_intervals() {
+        assert_eq!(fixed_interval_seconds("* * * * *"), Some(60));
+        assert_eq!(fixed_interval_seconds("0 * * * * *"), Some(60));
+        assert_eq!(fixed_interval_seconds("* * * * * *"), Some(1));
+        assert_eq!(fixed_interval_seconds("*/10 * * * * *"), Some(10));
+        assert_eq!(fixed_interval_seconds("*/15 * * * *"), Some(900));
+        assert_eq!(fixed_interval_seconds("0 */20 * * * *"), Some(1200));
+    }
+
+    #[test]
+    fn rejects_irregular_patterns() {
+        assert_eq!(fixed_interval_seconds("*/7 * * * *"), None);
+        assert_eq!(fixed_interval_seconds("30 * * * * *"), None);
+        assert_eq!(fixed_interval_seconds("0 9 * * *"), None);
+        assert_eq!(fixed_interval_seconds("* * * * 1-5"), None);
+        assert_eq!(fixed_interval_seconds("@hourly"), None);
+    }
+
+    #[test]
+    fn fast_path_matches_croner() {
+        for pattern in [
+            "* * * * *",
+            "0 * * * * *",
+            "* * * * * *",
+            "*/5 * * * *",
+            "*/30 * * * * *",
+            "0 */12 * * * *",
+        ] {
+            assert_eq!(
+                fast(pattern, 20_000),
+                generic(pattern, 20_000),
+                "{}",
+                pattern
+            );
+        }
+    }
+}
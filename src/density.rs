@@ -0,0 +1,159 @@
+// `cron_density(pattern)` estimates how many times a pattern fires per day on
+// average, computed from the field cardinalities rather than by iterating.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::fields::{parse_fields, Field};
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_once, OnceInitData};
+
+// Average month lengths over the 400 year Gregorian cycle.
+const DAYS_IN_MONTH: [f64; 12] = [
+    31.0, 28.2425, 31.0, 30.0, 31.0, 30.0, 31.0, 31.0, 30.0, 31.0, 30.0, 31.0,
+];
+const DAYS_IN_YEAR: f64 = 365.2425;
+const DAYS_IN_AVERAGE_MONTH: f64 = DAYS_IN_YEAR / 12.0;
+
+#[repr(C)]
+pub(crate) struct DensityBindData {
+    density: f64,
+}
+
+impl Free for DensityBindData {}
+
+// The expected number of days in `month` (0 based) selected by the
+// day-of-month field. `L` and `W` style items select one day each.
+fn days_of_month_matching(days: &Field, month: usize) -> f64 {
+    let plain: f64 = days
+        .values
+        .iter()
+        .map(|&day| match (month, day) {
+            (1, 29) => DAYS_IN_MONTH[1] - 28.0,
+            (_, day) if f64::from(day) <= DAYS_IN_MONTH[month] => 1.0,
+            _ => 0.0,
+        })
+        .sum();
+    plain + days.specials.len() as f64
+}
+
+// Estimates the average number of occurrences per day of a pattern's fields,
+// seconds first. `dom_and_dow` selects whether both restricted day fields
+// must match (AND) or either may (OR).
+pub(crate) fn density(fields: &[Field], dom_and_dow: bool) -> f64 {
+    let (seconds, minutes, hours) = (&fields[0], &fields[1], &fields[2]);
+    let (days, months, weekdays) = (&fields[3], &fields[4], &fields[5]);
+
+    let per_day = (seconds.values.len() * minutes.values.len() * hours.values.len()) as f64;
+
+    let month_days: f64 = months
+        .values
+        .iter()
+        .map(|&month| DAYS_IN_MONTH[month as usize - 1])
+        .sum();
+    let dom_days: f64 = months
+        .values
+        .iter()
+        .map(|&month| days_of_month_matching(days, month as usize - 1))
+        .sum();
+    // Items like `5#2` or `5L` select one day a month.
+    let weekday_probability =
+        weekdays.values.len() as f64 / 7.0 + weekdays.specials.len() as f64 / DAYS_IN_AVERAGE_MONTH;
+
+    let matching_days = match (days.wildcard, weekdays.wildcard) {
+        (true, true) => month_days,
+        (false, true) => dom_days,
+        (true, false) => month_days * weekday_probability,
+        (false, false) if dom_and_dow => dom_days * weekday_probability,
+        (false, false) => {
+            dom_days + month_days * weekday_probability - dom_days * weekday_probability
+        }
+    };
+
+    per_day * matching_days / DAYS_IN_YEAR
+}
+
+pub(crate) struct DensityVTab;
+
+impl VTab for DensityVTab {
+    type InitData = OnceInitData;
+    type BindData = DensityBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut DensityBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("density", LogicalType::new(LogicalTypeId::Double));
+
+        let pattern = pattern_parameter(bind, 0)?;
+        parse_pattern_or_error(&pattern)?;
+        (*data).density = density(&parse_fields(&pattern)?, true);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<DensityBindData>();
+        emit_once(init_info, output, Some((*bind_info).density));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(pattern: &str, dom_and_dow: bool) -> f64 {
+        density(&parse_fields(pattern).unwrap(), dom_and_dow)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, found {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn counts_fires_per_day() {
+        assert_close(estimate("@hourly", true), 24.0);
+        assert_close(estimate("@daily", true), 1.0);
+        assert_close(estimate("*/5 * * * *", true), 288.0);
+        assert_close(estimate("*/30 * * * * *", true), 2880.0);
+    }
+
+    #[test]
+    fn weighs_restricted_days() {
+        assert_close(estimate("0 9 * * 1-5", true), 5.0 / 7.0);
+        assert_close(estimate("0 0 1 * *", true), 12.0 / DAYS_IN_YEAR);
+        assert_close(estimate("0 0 * 2 *", true), DAYS_IN_MONTH[1] / DAYS_IN_YEAR);
+    }
+
+    #[test]
+    fn combines_both_day_fields() {
+        let both = 12.0 / DAYS_IN_YEAR / 7.0;
+        assert_close(estimate("0 0 13 * 5", true), both);
+        assert_close(
+            estimate("0 0 13 * 5", false),
+            12.0 / DAYS_IN_YEAR + 1.0 / 7.0 - both,
+        );
+    }
+}
@@ -0,0 +1,225 @@
+// `cron_describe_delta(old, new)` summarizes in English how a schedule
+// changes, e.g. "The new schedule fires 2 hours later and adds Saturday", for
+// reviewing schedule changes.
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::equivalent::canonical;
+use crate::fields::{impossible_date, parse_fields, FIELD_KINDS};
+use crate::humanize::plural;
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+// Joins items into an English enumeration, e.g. "a, b and c".
+fn enumerate(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [item] => item.clone(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
+    }
+}
+
+// Writes a number of seconds with its hours, minutes and seconds, e.g.
+// "1 hour 30 minutes".
+fn duration(seconds: i64) -> String {
+    let parts: Vec<String> = [
+        (seconds / 3600, "hour"),
+        (seconds % 3600 / 60, "minute"),
+        (seconds % 60, "second"),
+    ]
+    .iter()
+    .filter(|&&(count, _)| count > 0)
+    .map(|&(count, unit)| plural(count, unit))
+    .collect();
+    parts.join(" ")
+}
+
+// Names the values a field gains or loses, e.g. "Saturday" or "days 1 and 15".
+fn name_values(field: usize, values: &[u32]) -> String {
+    let names: Vec<String> = values
+        .iter()
+        .map(|&value| match field {
+            4 => MONTHS[value as usize - 1].to_string(),
+            5 => WEEKDAYS[value as usize].to_string(),
+            _ => value.to_string(),
+        })
+        .collect();
+    match (field, names.len()) {
+        (3, 1) => format!("day {}", names[0]),
+        (3, _) => format!("days {}", enumerate(&names)),
+        _ => enumerate(&names),
+    }
+}
+
+// Describes how the schedule `new` differs from `old`, "identical" when they
+// fire at the same instants.
+pub(crate) fn describe_delta(old: &str, new: &str) -> Result<String, String> {
+    parse_pattern_or_error(old)?;
+    parse_pattern_or_error(new)?;
+    let (old_fields, new_fields) = (parse_fields(old)?, parse_fields(new)?);
+    match (impossible_date(&old_fields), impossible_date(&new_fields)) {
+        (true, true) => return Ok("identical".to_string()),
+        (false, true) => return Ok("The new schedule never fires".to_string()),
+        (true, false) => return Ok("The old schedule never fires".to_string()),
+        (false, false) => {}
+    }
+    let (a, b) = (canonical(&old_fields), canonical(&new_fields));
+    if a == b {
+        return Ok("identical".to_string());
+    }
+
+    let mut clauses = Vec::new();
+    let changed = |field: usize| {
+        format!(
+            "changes the {} field from '{}' to '{}'",
+            FIELD_KINDS[field].name(),
+            old_fields[field].raw,
+            new_fields[field].raw
+        )
+    };
+
+    // The time of day, as seconds after midnight when it is a single one.
+    let time_of_day = |fields: &[(Vec<u32>, Vec<String>)]| match (
+        &fields[0].0[..],
+        &fields[1].0[..],
+        &fields[2].0[..],
+    ) {
+        ([second], [minute], [hour]) => Some(i64::from(hour * 3600 + minute * 60 + second)),
+        _ => None,
+    };
+    if a[..3] != b[..3] {
+        match (time_of_day(&a), time_of_day(&b)) {
+            (Some(before), Some(after)) if after > before => {
+                clauses.push(format!("fires {} later", duration(after - before)));
+            }
+            (Some(before), Some(after)) => {
+                clauses.push(format!("fires {} earlier", duration(before - after)));
+            }
+            _ => {
+                let per_day = |fields: &[(Vec<u32>, Vec<String>)]| -> usize {
+                    fields[..3].iter().map(|(values, _)| values.len()).product()
+                };
+                let (before, after) = (per_day(&a), per_day(&b));
+                if before != after {
+                    clauses.push(format!(
+                        "fires {} a day instead of {}",
+                        plural(after as i64, "time"),
+                        before
+                    ));
+                } else {
+                    clauses.extend((0..3).filter(|&i| a[i] != b[i]).map(changed));
+                }
+            }
+        }
+    }
+
+    // Days of the week first, they read most naturally.
+    for field in [5, 3, 4] {
+        let ((old_values, old_specials), (new_values, new_specials)) = (&a[field], &b[field]);
+        if old_values == new_values && old_specials == new_specials {
+            continue;
+        }
+        if old_specials != new_specials {
+            clauses.push(changed(field));
+            continue;
+        }
+        let added: Vec<u32> = new_values
+            .iter()
+            .filter(|x| !old_values.contains(x))
+            .copied()
+            .collect();
+        let removed: Vec<u32> = old_values
+            .iter()
+            .filter(|x| !new_values.contains(x))
+            .copied()
+            .collect();
+        // Narrowing down to a few values is clearer stated positively.
+        if added.is_empty() && removed.len() > new_values.len() && new_values.len() <= 3 {
+            let preposition = if field == 4 { "in" } else { "on" };
+            clauses.push(format!(
+                "only fires {} {}",
+                preposition,
+                name_values(field, new_values)
+            ));
+            continue;
+        }
+        if !added.is_empty() {
+            clauses.push(format!("adds {}", name_values(field, &added)));
+        }
+        if !removed.is_empty() {
+            clauses.push(format!("removes {}", name_values(field, &removed)));
+        }
+    }
+
+    Ok(format!("The new schedule {}", enumerate(&clauses)))
+}
+
+pub(crate) struct DescribeDeltaVTab;
+
+impl VTab for DescribeDeltaVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("description", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let old = pattern_parameter(bind, 0)?;
+        let new = pattern_parameter(bind, 1)?;
+        (*data).set(Some(describe_delta(&old, &new)?));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+}
@@ -0,0 +1,71 @@
+// `cron_describe_next(pattern, from)` describes the next occurrence for
+// messages, e.g. "in about 3 hours (2024-05-01 12:00)".
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::humanize::relative_duration;
+use crate::schedule::{
+    datetime_from_micros, next_after, parse_pattern_or_error, pattern_parameter, timezone_parameter,
+};
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+pub(crate) struct DescribeNextVTab;
+
+impl VTab for DescribeNextVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("description", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let from = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid from time")?;
+
+        (*data).set(next_after(&cron, from).map(|next| {
+            format!(
+                "{} ({})",
+                relative_duration(next - from),
+                next.format("%Y-%m-%d %H:%M")
+            )
+        }));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,160 @@
+// `cron_equivalent(a, b)` checks that two patterns describe exactly the same
+// schedule, however they are written.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::fields::{impossible_date, max_days_in_month, parse_fields, Field};
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct EquivalentBindData {
+    equivalent: bool,
+}
+
+impl Free for EquivalentBindData {}
+
+// The values and special items each field allows, seconds first, with the
+// days and months that can never coincide removed.
+//
+// Both day fields must match, so a wildcard is the same as listing every
+// value and is compared that way.
+pub(crate) fn canonical(fields: &[Field]) -> Vec<(Vec<u32>, Vec<String>)> {
+    let mut canonical: Vec<(Vec<u32>, Vec<String>)> = fields
+        .iter()
+        .map(|field| {
+            let mut specials = field.specials.clone();
+            specials.sort();
+            specials.dedup();
+            (field.values.clone(), specials)
+        })
+        .collect();
+
+    let days = &fields[3];
+    if !days.wildcard && days.specials.is_empty() {
+        // `31 * *` and `31 1,3,5,7,8,10,12 *` fire on the same days.
+        let first_day = days.values.first().copied().unwrap_or(0);
+        canonical[4]
+            .0
+            .retain(|&month| max_days_in_month(month) >= first_day);
+        let longest = canonical[4]
+            .0
+            .iter()
+            .map(|&month| max_days_in_month(month))
+            .max()
+            .unwrap_or(0);
+        canonical[3].0.retain(|&day| day <= longest);
+    }
+    canonical
+}
+
+// Whether two patterns fire at exactly the same instants.
+pub(crate) fn equivalent(a: &str, b: &str) -> Result<bool, String> {
+    parse_pattern_or_error(a)?;
+    parse_pattern_or_error(b)?;
+    let (a, b) = (parse_fields(a)?, parse_fields(b)?);
+    // Patterns that never fire are all the same schedule.
+    match (impossible_date(&a), impossible_date(&b)) {
+        (true, true) => Ok(true),
+        (false, false) => Ok(canonical(&a) == canonical(&b)),
+        _ => Ok(false),
+    }
+}
+
+pub(crate) struct EquivalentVTab;
+
+impl VTab for EquivalentVTab {
+    type InitData = OnceInitData;
+    type BindData = EquivalentBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut EquivalentBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("equivalent", LogicalType::new(LogicalTypeId::Boolean));
+
+        let a = pattern_parameter(bind, 0)?;
+        let b = pattern_parameter(bind, 1)?;
+        (*data).equivalent = equivalent(&a, &b)?;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<EquivalentBindData>();
+        emit_once(init_info, output, Some((*bind_info).equivalent));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same(a: &str, b: &str) -> bool {
+        equivalent(a, b).unwrap()
+    }
+
+    #[test]
+    fn names_match_numbers() {
+        assert!(same("0 0 * * 1", "0 0 * * MON"));
+        assert!(same("0 0 1 JAN *", "0 0 1 1 *"));
+    }
+
+    #[test]
+    fn ranges_steps_and_lists_match() {
+        assert!(same("0 9 * * 1-5", "0 9 * * 1,2,3,4,5"));
+        assert!(same("*/15 * * * *", "0,15,30,45 * * * *"));
+        assert!(same("0 */6 * * *", "0 0-23/6 * * *"));
+    }
+
+    #[test]
+    fn five_fields_match_six_with_zero_seconds() {
+        assert!(same("0 9 * * *", "0 0 9 * * *"));
+        assert!(!same("0 9 * * *", "30 0 9 * * *"));
+    }
+
+    #[test]
+    fn sunday_is_zero_or_seven() {
+        assert!(same("0 0 * * 0", "0 0 * * 7"));
+        assert!(same("0 0 * * 0-6", "0 0 * * *"));
+    }
+
+    #[test]
+    fn impossible_days_are_ignored() {
+        assert!(same("0 0 31 * *", "0 0 31 1,3,5,7,8,10,12 *"));
+        assert!(same("0 0 30 2 *", "0 0 31 4 *"));
+    }
+
+    #[test]
+    fn different_schedules_differ() {
+        assert!(!same("0 9 * * 1-5", "0 9 * * 1-6"));
+        assert!(!same("*/15 * * * *", "*/20 * * * *"));
+        assert!(!same("0 0 1 * *", "0 0 * * 1"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_errors() {
+        assert!(equivalent("0 0 * *", "0 0 * * *").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+// `cron_to_aws_eventbridge(pattern)` converts a pattern into the `cron(...)`
+// schedule expression of AWS EventBridge.
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::fields::{parse_fields, Field};
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+use crate::systemd::ranges;
+
+// Writes a numeric field, None if it uses items EventBridge can't express.
+fn numeric(field: &Field) -> Option<String> {
+    if !field.specials.is_empty() {
+        return None;
+    }
+    if field.wildcard {
+        return Some("*".to_string());
+    }
+    Some(ranges(&field.values, "-", |x| x.to_string()))
+}
+
+// Converts a pattern into an EventBridge expression, e.g. `0 9 * * 1-5` into
+// `cron(0 9 ? * 2-6 *)`. EventBridge has no seconds, adds a year, counts
+// weekdays from 1 for Sunday and requires `?` in one of the two day fields.
+//
+// Returns None for patterns firing at a second other than 0, restricting both
+// the day of the month and the weekday, or using `L`, `W` or `#`, which have
+// no clean EventBridge equivalent.
+pub(crate) fn to_eventbridge(pattern: &str) -> Result<Option<String>, String> {
+    parse_pattern_or_error(pattern)?;
+    let fields = parse_fields(pattern)?;
+    if fields[0].wildcard || fields[0].values != [0] {
+        return Ok(None);
+    }
+    // Minutes, hours, days and months.
+    let mut parts = match fields[1..5]
+        .iter()
+        .map(numeric)
+        .collect::<Option<Vec<String>>>()
+    {
+        Some(parts) => parts,
+        None => return Ok(None),
+    };
+
+    let weekdays = &fields[5];
+    if !weekdays.specials.is_empty() {
+        return Ok(None);
+    }
+    let weekday = if weekdays.wildcard || weekdays.values.len() == 7 {
+        "?".to_string()
+    } else if fields[3].wildcard {
+        parts[2] = "?".to_string();
+        let values: Vec<u32> = weekdays.values.iter().map(|&x| x + 1).collect();
+        ranges(&values, "-", |x| x.to_string())
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(format!(
+        "cron({} {} {} {} {} *)",
+        parts[0], parts[1], parts[2], parts[3], weekday
+    )))
+}
+
+pub(crate) struct EventBridgeVTab;
+
+impl VTab for EventBridgeVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("eventbridge", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let pattern = pattern_parameter(bind, 0)?;
+        (*data).set(to_eventbridge(&pattern)?);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eventbridge(pattern: &str) -> Option<String> {
+        to_eventbridge(pattern).unwrap()
+    }
+
+    #[test]
+    fn converts_patterns() {
+        assert_eq!(
+            eventbridge("0 9 * * 1-5").as_deref(),
+            Some("cron(0 9 ? * 2-6 *)")
+        );
+        assert_eq!(
+            eventbridge("30 2 1 * *").as_deref(),
+            Some("cron(30 2 1 * ? *)")
+        );
+        assert_eq!(
+            eventbridge("*/15 * * * *").as_deref(),
+            Some("cron(0,15,30,45 * * * ? *)")
+        );
+        assert_eq!(
+            eventbridge("0 0 0 1 1,7 *").as_deref(),
+            Some("cron(0 0 1 1,7 ? *)")
+        );
+    }
+
+    #[test]
+    fn weekdays_count_from_sunday() {
+        assert_eq!(
+            eventbridge("0 0 * * 0,6").as_deref(),
+            Some("cron(0 0 ? * 1,7 *)")
+        );
+        assert_eq!(
+            eventbridge("0 0 * * 5-7").as_deref(),
+            Some("cron(0 0 ? * 1,6-7 *)")
+        );
+    }
+
+    #[test]
+    fn unconvertible_patterns_give_none() {
+        // Both day fields restricted.
+        assert_eq!(eventbridge("0 0 1 * 1"), None);
+        // Seconds other than 0.
+        assert_eq!(eventbridge("30 0 9 * * *"), None);
+        assert_eq!(eventbridge("*/10 * * * * *"), None);
+        // Special items.
+        assert_eq!(eventbridge("0 0 L * *"), None);
+        assert_eq!(eventbridge("0 0 * * 5#2"), None);
+    }
+}
@@ -0,0 +1,371 @@
+// A lightweight expansion of cron patterns into the set of values each field
+// allows, used by the functions that inspect or compare patterns rather than
+// iterate them. Patterns are always validated by croner as well, this only
+// needs to understand the syntax croner accepts.
+
+// The fields of a pattern, in the order they appear in a six field pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FieldKind {
+    Seconds,
+    Minutes,
+    Hours,
+    DaysOfMonth,
+    Months,
+    DaysOfWeek,
+}
+
+pub(crate) const FIELD_KINDS: [FieldKind; 6] = [
+    FieldKind::Seconds,
+    FieldKind::Minutes,
+    FieldKind::Hours,
+    FieldKind::DaysOfMonth,
+    FieldKind::Months,
+    FieldKind::DaysOfWeek,
+];
+
+const MONTH_NAMES: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+
+const WEEKDAY_NAMES: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+impl FieldKind {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            FieldKind::Seconds => "second",
+            FieldKind::Minutes => "minute",
+            FieldKind::Hours => "hour",
+            FieldKind::DaysOfMonth => "day-of-month",
+            FieldKind::Months => "month",
+            FieldKind::DaysOfWeek => "day-of-week",
+        }
+    }
+
+    pub(crate) fn min(&self) -> u32 {
+        match self {
+            FieldKind::DaysOfMonth | FieldKind::Months => 1,
+            _ => 0,
+        }
+    }
+
+    // The largest value accepted, 7 is accepted as Sunday for weekdays.
+    pub(crate) fn max(&self) -> u32 {
+        match self {
+            FieldKind::Seconds | FieldKind::Minutes => 59,
+            FieldKind::Hours => 23,
+            FieldKind::DaysOfMonth => 31,
+            FieldKind::Months => 12,
+            FieldKind::DaysOfWeek => 7,
+        }
+    }
+
+    // Resolves a value written as a number or, for months and weekdays, as a
+    // three letter name.
+    fn value(&self, token: &str) -> Result<u32, String> {
+        let names: &[&str] = match self {
+            FieldKind::Months => &MONTH_NAMES,
+            FieldKind::DaysOfWeek => &WEEKDAY_NAMES,
+            _ => &[],
+        };
+        let upper = token.to_uppercase();
+        if let Some(index) = names.iter().position(|name| *name == upper) {
+            return Ok(index as u32 + self.min());
+        }
+        let value: u32 = token
+            .parse()
+            .map_err(|_| format!("Invalid {} field value '{}'", self.name(), token))?;
+        if value < self.min() || value > self.max() {
+            return Err(format!(
+                "{} field value {} out of range {}-{}",
+                self.name(),
+                value,
+                self.min(),
+                self.max()
+            ));
+        }
+        Ok(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Field {
+    pub(crate) kind: FieldKind,
+    // The field as it was written.
+    pub(crate) raw: String,
+    // The sorted plain values the field allows, with Sunday always 0.
+    pub(crate) values: Vec<u32>,
+    // Whether the field is unrestricted (`*` or `?`).
+    pub(crate) wildcard: bool,
+    // Tokens that cannot be expanded into plain values, like `L`, `15W` or
+    // `5#3`.
+    pub(crate) specials: Vec<String>,
+}
+
+// Expands one field of a pattern.
+pub(crate) fn parse_field(kind: FieldKind, raw: &str) -> Result<Field, String> {
+    let mut values = Vec::new();
+    let mut specials = Vec::new();
+    let wildcard = raw == "*" || (raw == "?" && is_day_field(kind));
+
+    for item in raw.split(',') {
+        if item.is_empty() {
+            return Err(format!("Empty item in {} field '{}'", kind.name(), raw));
+        }
+        if is_special(kind, item) {
+            specials.push(item.to_uppercase());
+            continue;
+        }
+
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("Invalid step in {} field '{}'", kind.name(), raw))?;
+                if step == 0 {
+                    return Err(format!("Zero step in {} field '{}'", kind.name(), raw));
+                }
+                (range, step)
+            }
+            None => (item, 1),
+        };
+
+        let (low, high) = if range == "*" || (range == "?" && is_day_field(kind)) {
+            // Sunday is reachable as 0, so a wildcard needs no 7.
+            let max = match kind {
+                FieldKind::DaysOfWeek => 6,
+                _ => kind.max(),
+            };
+            (kind.min(), max)
+        } else if let Some((low, high)) = range.split_once('-') {
+            let (low, high) = (kind.value(low)?, kind.value(high)?);
+            if low > high {
+                return Err(format!(
+                    "Invalid range '{}' in {} field",
+                    range,
+                    kind.name()
+                ));
+            }
+            (low, high)
+        } else {
+            let low = kind.value(range)?;
+            // `n/s` means every s starting from n.
+            let high = if item.contains('/') { kind.max() } else { low };
+            (low, high)
+        };
+
+        values.extend((low..=high).step_by(step as usize));
+    }
+
+    if kind == FieldKind::DaysOfWeek {
+        for value in values.iter_mut() {
+            if *value == 7 {
+                *value = 0;
+            }
+        }
+    }
+    values.sort_unstable();
+    values.dedup();
+
+    Ok(Field {
+        kind,
+        raw: raw.to_string(),
+        values,
+        wildcard,
+        specials,
+    })
+}
+
+fn is_day_field(kind: FieldKind) -> bool {
+    matches!(kind, FieldKind::DaysOfMonth | FieldKind::DaysOfWeek)
+}
+
+// Whether an item uses the last/nearest-weekday/nth-weekday extensions.
+fn is_special(kind: FieldKind, item: &str) -> bool {
+    let upper = item.to_uppercase();
+    match kind {
+        FieldKind::DaysOfMonth => upper.contains('L') || upper.ends_with('W'),
+        FieldKind::DaysOfWeek => upper.contains('#') || (upper.ends_with('L') && upper.len() > 1),
+        _ => false,
+    }
+}
+
+// Expands a nickname like `@daily` into the equivalent five field pattern.
+pub(crate) fn expand_nickname(pattern: &str) -> Option<&'static str> {
+    match pattern.trim().to_lowercase().as_str() {
+        "@yearly" | "@annually" => Some("0 0 1 1 *"),
+        "@monthly" => Some("0 0 1 * *"),
+        "@weekly" => Some("0 0 * * 0"),
+        "@daily" | "@midnight" => Some("0 0 * * *"),
+        "@hourly" => Some("0 * * * *"),
+        _ => None,
+    }
+}
+
+// Splits a pattern into its raw fields, always returning six with a `0`
+// seconds field added to five field patterns.
+pub(crate) fn split_fields(pattern: &str) -> Result<Vec<String>, String> {
+    let pattern = expand_nickname(pattern).unwrap_or(pattern);
+    let mut fields: Vec<String> = pattern.split_whitespace().map(String::from).collect();
+    match fields.len() {
+        5 => fields.insert(0, "0".to_string()),
+        6 => {}
+        count => {
+            return Err(format!(
+                "Expected a cron pattern with 5 or 6 fields, found {}",
+                count
+            ))
+        }
+    }
+    Ok(fields)
+}
+
+// Replaces both day fields of a pattern with `*`, returning six fields.
+pub(crate) fn any_day(pattern: &str) -> Result<String, String> {
+    let mut fields = split_fields(pattern)?;
+    fields[3] = "*".to_string();
+    fields[5] = "*".to_string();
+    Ok(fields.join(" "))
+}
+
+// Replaces the field of `kind` in a pattern with a list of values, returning
+// six fields.
+pub(crate) fn with_values(
+    pattern: &str,
+    kind: FieldKind,
+    values: &[u32],
+) -> Result<String, String> {
+    let mut fields = split_fields(pattern)?;
+    let index = FIELD_KINDS.iter().position(|&x| x == kind).unwrap_or(0);
+    fields[index] = values
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    Ok(fields.join(" "))
+}
+
+// Moves the seconds field of a `minute hour day-of-month month day-of-week
+// second` pattern, as written by some legacy systems, to the front.
+pub(crate) fn seconds_first(pattern: &str) -> Result<String, String> {
+    let mut fields: Vec<&str> = pattern.split_whitespace().collect();
+    if fields.len() != 6 {
+        return Err(format!(
+            "A seconds last pattern needs 6 fields ending with the seconds, found {} in '{}'",
+            fields.len(),
+            pattern
+        ));
+    }
+    fields.rotate_right(1);
+    Ok(fields.join(" "))
+}
+
+// Expands every field of a pattern, seconds first.
+pub(crate) fn parse_fields(pattern: &str) -> Result<Vec<Field>, String> {
+    split_fields(pattern)?
+        .iter()
+        .zip(FIELD_KINDS.iter())
+        .map(|(raw, &kind)| parse_field(kind, raw))
+        .collect()
+}
+
+// The most days a month (1 based) can have.
+pub(crate) fn max_days_in_month(month: u32) -> u32 {
+    match month {
+        2 => 29,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Whether the day-of-month field only names days that none of the months in
+// the month field have, like `0 0 31 4 *`.
+pub(crate) fn impossible_date(fields: &[Field]) -> bool {
+    let (days, months) = (&fields[3], &fields[4]);
+    if days.wildcard || !days.specials.is_empty() {
+        return false;
+    }
+    let longest = months
+        .values
+        .iter()
+        .map(|&month| max_days_in_month(month))
+        .max()
+        .unwrap_or(0);
+    days.values.iter().all(|&day| day > longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_into_six_fields() {
+        assert_eq!(
+            split_fields("0 9 * * 1-5").unwrap(),
+            vec!["0", "0", "9", "*", "*", "1-5"]
+        );
+        assert_eq!(
+            split_fields(" 30  0 9 * * * ").unwrap(),
+            vec!["30", "0", "9", "*", "*", "*"]
+        );
+        assert_eq!(
+            split_fields("@weekly").unwrap(),
+            vec!["0", "0", "0", "*", "*", "0"]
+        );
+        assert!(split_fields("0 9 * *").is_err());
+        assert!(split_fields("0 0 9 * * * 2024").is_err());
+    }
+
+    #[test]
+    fn moves_trailing_seconds_first() {
+        assert_eq!(seconds_first("0 9 * * 1-5 30").unwrap(), "30 0 9 * * 1-5");
+        assert!(seconds_first("0 9 * * 1-5").is_err());
+    }
+
+    #[test]
+    fn replaces_field_values() {
+        assert_eq!(
+            with_values("0 9 * * 1-5", FieldKind::Hours, &[9, 17]).unwrap(),
+            "0 0 9,17 * * 1-5"
+        );
+        assert_eq!(
+            with_values("@hourly", FieldKind::Minutes, &[0, 30]).unwrap(),
+            "0 0,30 * * * *"
+        );
+        assert_eq!(
+            with_values("15 0 0 * * *", FieldKind::DaysOfMonth, &[1]).unwrap(),
+            "15 0 0 1 * *"
+        );
+        assert_eq!(
+            with_values("0 0 * * *", FieldKind::Seconds, &[0, 30]).unwrap(),
+            "0,30 0 0 * * *"
+        );
+    }
+
+    #[test]
+    fn expands_fields() {
+        let field = |kind, raw| parse_field(kind, raw).unwrap();
+        assert_eq!(field(FieldKind::Minutes, "*/20").values, vec![0, 20, 40]);
+        assert_eq!(field(FieldKind::Minutes, "5/20").values, vec![5, 25, 45]);
+        assert_eq!(
+            field(FieldKind::Hours, "9-11,14").values,
+            vec![9, 10, 11, 14]
+        );
+        assert_eq!(field(FieldKind::Months, "JAN-MAR").values, vec![1, 2, 3]);
+        assert_eq!(
+            field(FieldKind::DaysOfWeek, "MON-FRI").values,
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(field(FieldKind::DaysOfWeek, "5-7").values, vec![0, 5, 6]);
+        assert_eq!(
+            field(FieldKind::DaysOfWeek, "*").values,
+            (0..=6).collect::<Vec<u32>>()
+        );
+        assert!(field(FieldKind::DaysOfMonth, "?").wildcard);
+        assert_eq!(field(FieldKind::DaysOfMonth, "L").specials, vec!["L"]);
+        assert_eq!(field(FieldKind::DaysOfWeek, "5#2").specials, vec!["5#2"]);
+
+        assert!(parse_field(FieldKind::Minutes, "60").is_err());
+        assert!(parse_field(FieldKind::Minutes, "*/0").is_err());
+        assert!(parse_field(FieldKind::Hours, "5-3").is_err());
+    }
+}
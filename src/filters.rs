@@ -0,0 +1,160 @@
+// Post filters applied to generated occurrences, restricting them to a set of
+// months, hours or weekdays in addition to the pattern's own constraints.
+
+use chrono::{DateTime, Datelike, Months, NaiveDate, TimeDelta, Timelike};
+use chrono_tz::Tz;
+use duckdb::vtab::BindInfo;
+
+use crate::business_day::is_business_day;
+use crate::params::parse_int_list;
+
+// Selects the day a number of days before the end of each month, for finance
+// schedules like "3 business days before month end".
+#[derive(Clone, Copy)]
+pub(crate) struct MonthEnd {
+    // 0 is the last day.
+    days_before: u32,
+    // Count only weekdays, 0 then being the last weekday of the month.
+    business_days: bool,
+}
+
+impl MonthEnd {
+    // The selected day of a month, None when the month is too short to have
+    // it.
+    fn day(&self, year: i32, month: u32) -> Option<NaiveDate> {
+        let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let last = first.checked_add_months(Months::new(1))? - TimeDelta::days(1);
+        let mut days = std::iter::successors(Some(last), |&date| date.pred_opt())
+            .take_while(|date| date.month() == month);
+        if self.business_days {
+            days.filter(|&date| is_business_day(date, &[]))
+                .nth(self.days_before as usize)
+        } else {
+            days.nth(self.days_before as usize)
+        }
+    }
+}
+
+// Each filter is a bitmask of the allowed values, None means unfiltered.
+#[derive(Clone, Copy)]
+pub(crate) struct OccurrenceFilters {
+    months: Option<u32>,
+    hours: Option<u32>,
+    weekdays: Option<u32>,
+    month_end: Option<MonthEnd>,
+}
+
+// Reads an optional LIST(INTEGER) named parameter into a bitmask, checking
+// every value lies within `min..=max`.
+fn mask_parameter(bind: &BindInfo, name: &str, min: i64, max: i64) -> Result<Option<u32>, String> {
+    let value = match bind.get_named_parameter(name) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let mut mask = 0;
+    for x in parse_int_list(&value.to_string())? {
+        if x < min || x > max {
+            return Err(format!(
+                "{} value {} is out of range {}-{}",
+                name, x, min, max
+            ));
+        }
+        mask |= 1 << x;
+    }
+    Ok(Some(mask))
+}
+
+impl OccurrenceFilters {
+    pub(crate) fn from_bind(bind: &BindInfo) -> Result<Self, String> {
+        let weekdays = mask_parameter(bind, "weekday_in", 0, 7)?.map(|mask| {
+            // Like cron, both 0 and 7 mean Sunday.
+            if mask & (1 << 7) != 0 {
+                mask | 1
+            } else {
+                mask
+            }
+        });
+        let business_days = match bind.get_named_parameter("business_days") {
+            Some(value) => value.to_string() == "true",
+            None => false,
+        };
+        let month_end = match bind.get_named_parameter("from_month_end") {
+            Some(value) => {
+                let days = value.to_int64();
+                let days_before = u32::try_from(days)
+                    .map_err(|_| format!("from_month_end must not be negative, found {}", days))?;
+                Some(MonthEnd {
+                    days_before,
+                    business_days,
+                })
+            }
+            None if business_days => {
+                return Err("business_days requires from_month_end".to_string())
+            }
+            None => None,
+        };
+        Ok(OccurrenceFilters {
+            months: mask_parameter(bind, "month_in", 1, 12)?,
+            hours: mask_parameter(bind, "hour_in", 0, 23)?,
+            weekdays,
+            month_end,
+        })
+    }
+
+    // Checks the occurrence's local time against every filter.
+    pub(crate) fn matches(&self, x: &DateTime<Tz>) -> bool {
+        let allowed = |mask: Option<u32>, value: u32| match mask {
+            Some(mask) => mask & (1 << value) != 0,
+            None => true,
+        };
+        allowed(self.months, x.month())
+            && allowed(self.hours, x.hour())
+            && allowed(self.weekdays, x.weekday().num_days_from_sunday())
+            && match self.month_end {
+                Some(month_end) => month_end.day(x.year(), x.month()) == Some(x.date_naive()),
+                None => true,
+            }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(days_before: u32, business_days: bool, year: i32, month: u32) -> Option<u32> {
+        let month_end = MonthEnd {
+            days_before,
+            business_days,
+        };
+        month_end.day(year, month).map(|date| date.day())
+    }
+
+    #[test]
+    fn zero_is_the_last_day() {
+        assert_eq!(day(0, false, 2024, 1), Some(31));
+        assert_eq!(day(0, false, 2024, 2), Some(29));
+        assert_eq!(day(0, false, 2023, 2), Some(28));
+        assert_eq!(day(0, false, 2024, 4), Some(30));
+        assert_eq!(day(0, false, 2024, 12), Some(31));
+    }
+
+    #[test]
+    fn counts_back_from_the_last_day() {
+        assert_eq!(day(3, false, 2024, 1), Some(28));
+        assert_eq!(day(3, false, 2024, 2), Some(26));
+        assert_eq!(day(3, false, 2024, 4), Some(27));
+        assert_eq!(day(28, false, 2024, 2), Some(1));
+        assert_eq!(day(29, false, 2024, 2), None);
+    }
+
+    #[test]
+    fn business_days_skip_weekends() {
+        // March and June 2024 both end on a Sunday.
+        assert_eq!(day(0, true, 2024, 3), Some(29));
+        assert_eq!(day(0, true, 2024, 6), Some(28));
+        assert_eq!(day(1, true, 2024, 6), Some(27));
+        // January 2024 ends on a Wednesday, five days back skips a weekend.
+        assert_eq!(day(0, true, 2024, 1), Some(31));
+        assert_eq!(day(5, true, 2024, 1), Some(24));
+    }
+}
@@ -0,0 +1,246 @@
+// `cron_fire_indices(pattern, start, until)` returns the positions of the
+// occurrences on a grid of seconds, minutes, hours or days counted from
+// `start`, for building sparse bitmaps of schedule activity.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, pattern_parameter,
+    search_limit, timezone_parameter,
+};
+
+#[repr(C)]
+pub(crate) struct FireIndicesBindData {
+    pattern: *mut Cron,
+    start: DateTime<Tz>,
+    // Exclusive.
+    until: DateTime<Tz>,
+    // The length of a grid cell in seconds.
+    unit_seconds: i64,
+}
+
+impl Free for FireIndicesBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.pattern.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.pattern));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct FireIndicesInitData {
+    done: bool,
+    // The last occurrence considered, generation resumes strictly after it.
+    cursor: Option<DateTime<Tz>>,
+    // The last index emitted. Several occurrences can share a cell, which is
+    // only returned once.
+    last_index: Option<i64>,
+}
+
+impl Free for FireIndicesInitData {}
+
+const DAY_SECONDS: i64 = 86400;
+
+// Parses the `unit` named parameter into the length of a cell in seconds.
+fn unit_seconds(unit: &str) -> Result<i64, String> {
+    match unit {
+        "second" => Ok(1),
+        "minute" => Ok(60),
+        "hour" => Ok(3600),
+        "day" => Ok(DAY_SECONDS),
+        _ => Err(format!(
+            "Invalid unit '{}', expected 'second', 'minute', 'hour' or 'day'",
+            unit
+        )),
+    }
+}
+
+// Checks that the range holds at least one instant and keeps indices small
+// enough for any bitmap built from them.
+fn check_range(start: DateTime<Tz>, until: DateTime<Tz>) -> Result<(), String> {
+    if until <= start {
+        return Err("The until time of cron_fire_indices must be after the start time".to_string());
+    }
+    if until > search_limit(start) {
+        return Err("The range of cron_fire_indices may span at most five years".to_string());
+    }
+    Ok(())
+}
+
+// The cell of an occurrence. Days are counted on the local calendar, as a
+// day lasting 23 or 25 hours would otherwise shift later days into the
+// wrong cell, smaller units as elapsed time.
+fn fire_index(start: DateTime<Tz>, x: DateTime<Tz>, unit_seconds: i64) -> i64 {
+    if unit_seconds == DAY_SECONDS {
+        return x
+            .date_naive()
+            .signed_duration_since(start.date_naive())
+            .num_days();
+    }
+    (x - start).num_seconds() / unit_seconds
+}
+
+pub(crate) struct FireIndicesVTab;
+
+impl VTab for FireIndicesVTab {
+    type InitData = FireIndicesInitData;
+    type BindData = FireIndicesBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut FireIndicesBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).pattern = null_mut();
+        bind.add_result_column("index", LogicalType::new(LogicalTypeId::Bigint));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        (*data).start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        (*data).until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        check_range((*data).start, (*data).until)?;
+        (*data).unit_seconds = match bind.get_named_parameter("unit") {
+            Some(value) => unit_seconds(&value.to_string())?,
+            None => 1,
+        };
+        (*data).pattern = Box::into_raw(Box::new(cron));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut FireIndicesInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        (*data).cursor = None;
+        (*data).last_index = None;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<FireIndicesInitData>();
+        let bind_info = func.get_bind_data::<FireIndicesBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let pattern = &*(*bind_info).pattern;
+        let start = (*bind_info).start;
+        let occurrences: Box<dyn Iterator<Item = DateTime<Tz>>> = match (*init_info).cursor {
+            Some(cursor) => Box::new(
+                pattern
+                    .iter_after(cursor)
+                    .take_while(|&x| x < (*bind_info).until),
+            ),
+            None => Box::new(occurrences_between(pattern, start, (*bind_info).until)),
+        };
+
+        let mut indices: Vec<i64> = Vec::new();
+        for x in occurrences {
+            (*init_info).cursor = Some(x);
+            let index = fire_index(start, x, (*bind_info).unit_seconds);
+            if (*init_info).last_index == Some(index) {
+                continue;
+            }
+            (*init_info).last_index = Some(index);
+            indices.push(index);
+            if indices.len() == max_items {
+                break;
+            }
+        }
+
+        output.flat_vector(0).copy(&indices);
+        output.set_len(indices.len());
+        (*init_info).done = indices.len() < max_items;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![
+            (
+                "timezone".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            ("unit".to_string(), LogicalType::new(LogicalTypeId::Varchar)),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::America::New_York;
+
+    fn local(date: (i32, u32, u32), hour: u32) -> DateTime<Tz> {
+        New_York
+            .with_ymd_and_hms(date.0, date.1, date.2, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn counts_days_on_the_local_calendar() {
+        // 2024-03-10 lasts 23 hours, so its next midnight is only 47 hours
+        // after the one before it.
+        let start = local((2024, 3, 9), 0);
+        assert_eq!(fire_index(start, local((2024, 3, 10), 0), DAY_SECONDS), 1);
+        assert_eq!(fire_index(start, local((2024, 3, 11), 0), DAY_SECONDS), 2);
+        assert_eq!(fire_index(start, local((2024, 3, 11), 23), DAY_SECONDS), 2);
+
+        // 2024-11-03 lasts 25 hours.
+        let start = local((2024, 11, 3), 0);
+        assert_eq!(fire_index(start, local((2024, 11, 3), 23), DAY_SECONDS), 0);
+        assert_eq!(fire_index(start, local((2024, 11, 4), 0), DAY_SECONDS), 1);
+
+        // Cells are calendar days even when the range starts mid-day.
+        let start = local((2024, 6, 1), 12);
+        assert_eq!(fire_index(start, local((2024, 6, 1), 23), DAY_SECONDS), 0);
+        assert_eq!(fire_index(start, local((2024, 6, 2), 9), DAY_SECONDS), 1);
+    }
+
+    #[test]
+    fn counts_smaller_units_as_elapsed_time() {
+        // 03:00 is two hours after midnight on 2024-03-10.
+        let start = local((2024, 3, 10), 0);
+        assert_eq!(fire_index(start, local((2024, 3, 10), 3), 3600), 2);
+        assert_eq!(fire_index(start, local((2024, 3, 10), 3), 60), 120);
+        assert_eq!(
+            fire_index(start, start + chrono::TimeDelta::seconds(59), 60),
+            0
+        );
+    }
+
+    #[test]
+    fn rejects_empty_ranges() {
+        let start = local((2024, 1, 1), 0);
+        assert!(check_range(start, local((2024, 1, 2), 0)).is_ok());
+        assert!(check_range(start, start).is_err());
+        assert!(check_range(start, local((2023, 12, 31), 0)).is_err());
+        assert!(check_range(start, local((2030, 1, 2), 0)).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+// `cron_fires_between(pattern, start, until)` checks whether a schedule fires
+// at all within a window, stopping at the first occurrence.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    datetime_from_micros, never_fires, occurrences_between, parse_pattern_or_error,
+    pattern_parameter, timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct FiresBetweenBindData {
+    fires: bool,
+}
+
+impl Free for FiresBetweenBindData {}
+
+pub(crate) struct FiresBetweenVTab;
+
+impl VTab for FiresBetweenVTab {
+    type InitData = OnceInitData;
+    type BindData = FiresBetweenBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut FiresBetweenBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("fires", LogicalType::new(LogicalTypeId::Boolean));
+
+        let pattern = pattern_parameter(bind, 0)?;
+        let cron = parse_pattern_or_error(&pattern)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+
+        (*data).fires =
+            !never_fires(&pattern) && occurrences_between(&cron, start, until).next().is_some();
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<FiresBetweenBindData>();
+        emit_once(init_info, output, Some((*bind_info).fires));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,103 @@
+// `cron_first_after_gap(pattern, start, until, gap)` finds the first
+// occurrence that resumes activity after an idle period longer than `gap`,
+// the pointwise companion of `cron_gaps`.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, pattern_parameter,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct FirstAfterGapBindData {
+    occurrence: Option<i64>,
+}
+
+impl Free for FirstAfterGapBindData {}
+
+// The first occurrence within `[start, until)` more than `gap` after the
+// previous one, which must be in the window as well. The search stops at it.
+pub(crate) fn first_after_gap(
+    cron: &Cron,
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    gap: TimeDelta,
+) -> Option<DateTime<Tz>> {
+    let mut occurrences = occurrences_between(cron, start, until);
+    let mut previous = occurrences.next()?;
+    for x in occurrences {
+        if x - previous > gap {
+            return Some(x);
+        }
+        previous = x;
+    }
+    None
+}
+
+pub(crate) struct FirstAfterGapVTab;
+
+impl VTab for FirstAfterGapVTab {
+    type InitData = OnceInitData;
+    type BindData = FirstAfterGapBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut FirstAfterGapBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).occurrence = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        let gap = parse_interval(&bind.get_parameter(3).to_string())?;
+
+        (*data).occurrence = first_after_gap(&cron, start, until, gap).map(|x| x.timestamp());
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<FirstAfterGapBindData>();
+        emit_once(init_info, output, (*bind_info).occurrence);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Interval),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
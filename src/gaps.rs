@@ -0,0 +1,148 @@
+// `cron_gaps(pattern, start, until, threshold)` finds the idle periods between
+// consecutive occurrences that last longer than a threshold.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, never_fires, occurrences_between, parse_pattern_or_error,
+    pattern_parameter, timezone_parameter,
+};
+
+#[repr(C)]
+pub(crate) struct GapsBindData {
+    pattern: *mut Cron,
+    start: DateTime<Tz>,
+    // Exclusive.
+    until: DateTime<Tz>,
+    threshold: TimeDelta,
+}
+
+impl Free for GapsBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.pattern.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.pattern));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct GapsInitData {
+    done: bool,
+    // The end of the last gap emitted, the search resumes from it.
+    cursor: Option<DateTime<Tz>>,
+}
+
+impl Free for GapsInitData {}
+
+pub(crate) struct GapsVTab;
+
+impl VTab for GapsVTab {
+    type InitData = GapsInitData;
+    type BindData = GapsBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut GapsBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).pattern = null_mut();
+        bind.add_result_column("gap_start", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("gap_end", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("gap_seconds", LogicalType::new(LogicalTypeId::Bigint));
+
+        let pattern = pattern_parameter(bind, 0)?;
+        let cron = parse_pattern_or_error(&pattern)?;
+        let timezone = timezone_parameter(bind)?;
+        (*data).start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        (*data).until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        (*data).threshold = parse_interval(&bind.get_parameter(3).to_string())?;
+        // A pattern that never fires has no gaps, and croner would search far
+        // ahead for its first occurrence, so it is left null.
+        if !never_fires(&pattern) {
+            (*data).pattern = Box::into_raw(Box::new(cron));
+        }
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut GapsInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        (*data).cursor = None;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<GapsInitData>();
+        let bind_info = func.get_bind_data::<GapsBindData>();
+
+        if (*init_info).done || (*bind_info).pattern.is_null() {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let pattern = &*(*bind_info).pattern;
+        let threshold = (*bind_info).threshold;
+        let from = (*init_info).cursor.unwrap_or((*bind_info).start);
+
+        let mut occurrences = occurrences_between(pattern, from, (*bind_info).until);
+        let mut gaps: Vec<(DateTime<Tz>, DateTime<Tz>)> = Vec::new();
+        if let Some(mut previous) = occurrences.next() {
+            for x in occurrences {
+                if x - previous > threshold {
+                    gaps.push((previous, x));
+                    if gaps.len() == max_items {
+                        break;
+                    }
+                }
+                previous = x;
+            }
+        }
+
+        if let Some(&(_, end)) = gaps.last() {
+            (*init_info).cursor = Some(end);
+        }
+        let starts: Vec<i64> = gaps.iter().map(|(x, _)| x.timestamp()).collect();
+        let ends: Vec<i64> = gaps.iter().map(|(_, x)| x.timestamp()).collect();
+        let lengths: Vec<i64> = gaps.iter().map(|(a, b)| (*b - *a).num_seconds()).collect();
+        output.flat_vector(0).copy(&starts);
+        output.flat_vector(1).copy(&ends);
+        output.flat_vector(2).copy(&lengths);
+        output.set_len(gaps.len());
+        (*init_info).done = gaps.len() < max_items;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Interval),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
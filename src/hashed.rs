@@ -0,0 +1,211 @@
+// Support for the `H` notation of Jenkins, which spreads the load of many
+// jobs by replacing `H` with a value derived from a hash of the job's name.
+
+use duckdb::vtab::BindInfo;
+
+use crate::fields::{expand_nickname, FieldKind, FIELD_KINDS};
+
+// How `H` is resolved for a `cron()` call.
+pub(crate) struct HashSeed {
+    job_id: Option<String>,
+    // Resolve `H` to the lowest value of its range without a `job_id`,
+    // rather than failing.
+    fallback_to_min: bool,
+}
+
+impl HashSeed {
+    // Reads the `job_id` and `hash_fallback` named parameters.
+    pub(crate) fn from_bind(bind: &BindInfo) -> Result<HashSeed, String> {
+        let fallback_to_min = match bind.get_named_parameter("hash_fallback") {
+            Some(value) => match value.to_string().to_lowercase().as_str() {
+                "error" => false,
+                "min" => true,
+                other => {
+                    return Err(format!(
+                        "Invalid hash_fallback '{}', expected 'error' or 'min'",
+                        other
+                    ))
+                }
+            },
+            None => false,
+        };
+        Ok(HashSeed {
+            job_id: bind
+                .get_named_parameter("job_id")
+                .map(|value| value.to_string()),
+            fallback_to_min,
+        })
+    }
+
+    // Replaces every `H` item of a pattern with the value it stands for,
+    // leaving patterns without one untouched.
+    pub(crate) fn resolve(&self, pattern: &str) -> Result<String, String> {
+        if expand_nickname(pattern).is_some() {
+            return Ok(pattern.to_string());
+        }
+        let fields: Vec<&str> = pattern.split_whitespace().collect();
+        // Five field patterns have no seconds.
+        let kinds = match fields.len() {
+            5 => &FIELD_KINDS[1..],
+            _ => &FIELD_KINDS[..],
+        };
+        let mut resolved = Vec::with_capacity(fields.len());
+        for (field, &kind) in fields.iter().zip(kinds.iter()) {
+            let items: Result<Vec<String>, String> = field
+                .split(',')
+                .map(|item| self.resolve_item(kind, item))
+                .collect();
+            resolved.push(items?.join(","));
+        }
+        // Let croner report patterns with the wrong number of fields.
+        resolved.extend(fields.iter().skip(kinds.len()).map(|x| x.to_string()));
+        Ok(resolved.join(" "))
+    }
+
+    // Resolves `H`, `H(low-high)`, `H/step` and `H(low-high)/step`.
+    fn resolve_item(&self, kind: FieldKind, item: &str) -> Result<String, String> {
+        let rest = match item.strip_prefix('H') {
+            Some(rest) if rest.is_empty() || rest.starts_with('(') || rest.starts_with('/') => rest,
+            // Names like `THU` are not hashes.
+            _ => return Ok(item.to_string()),
+        };
+        let invalid = || format!("Invalid hash '{}' in {} field", item, kind.name());
+
+        let (range, step) = match rest.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, Some(step))
+            }
+            None => (rest, None),
+        };
+        let (low, high) = match range {
+            "" => hash_range(kind),
+            _ => {
+                let (low, high) = range
+                    .strip_prefix('(')
+                    .and_then(|x| x.strip_suffix(')'))
+                    .and_then(|x| x.split_once('-'))
+                    .ok_or_else(invalid)?;
+                let low: u32 = low.parse().map_err(|_| invalid())?;
+                let high: u32 = high.parse().map_err(|_| invalid())?;
+                if low > high || low < kind.min() || high > kind.max() {
+                    return Err(invalid());
+                }
+                (low, high)
+            }
+        };
+
+        let hash = match (&self.job_id, self.fallback_to_min) {
+            (Some(job_id), _) => fnv1a(
+                FNV_OFFSET_BASIS,
+                format!("{}:{}", job_id, kind.name()).as_bytes(),
+            ),
+            (None, true) => 0,
+            (None, false) => {
+                return Err(format!(
+                    "The pattern uses '{}', which requires a job_id",
+                    item
+                ))
+            }
+        };
+        Ok(match step {
+            Some(step) => {
+                let offset = (hash % u64::from(step)) as u32;
+                format!("{}-{}/{}", (low + offset).min(high), high, step)
+            }
+            None => (low + (hash % u64::from(high - low + 1)) as u32).to_string(),
+        })
+    }
+}
+
+// The values a bare `H` chooses from. Like Jenkins, days of the month stop at
+// 28 so the job runs every month.
+fn hash_range(kind: FieldKind) -> (u32, u32) {
+    match kind {
+        FieldKind::DaysOfMonth => (1, 28),
+        FieldKind::DaysOfWeek => (0, 6),
+        _ => (kind.min(), kind.max()),
+    }
+}
+
+// The starting state of `fnv1a`.
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+// Folds bytes into a 64 bit FNV-1a hash. It is spelled out so results are
+// the same across builds and platforms, unlike the hashers of the standard
+// library, and can be built up incrementally from `FNV_OFFSET_BASIS`.
+pub(crate) fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seed(job_id: Option<&str>, fallback_to_min: bool) -> HashSeed {
+        HashSeed {
+            job_id: job_id.map(String::from),
+            fallback_to_min,
+        }
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b""), FNV_OFFSET_BASIS);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(FNV_OFFSET_BASIS, b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn same_job_resolves_the_same() {
+        let pattern = "H H(0-5) * * H";
+        let first = seed(Some("nightly-backup"), false)
+            .resolve(pattern)
+            .unwrap();
+        let again = seed(Some("nightly-backup"), false)
+            .resolve(pattern)
+            .unwrap();
+        assert_eq!(first, again);
+        assert!(!first.contains('H'));
+    }
+
+    #[test]
+    fn resolved_values_stay_in_range() {
+        let resolved = seed(Some("report"), false)
+            .resolve("H(10-20) H(0-5) H * *")
+            .unwrap();
+        let values: Vec<u32> = resolved
+            .split_whitespace()
+            .take(3)
+            .map(|x| x.parse().unwrap())
+            .collect();
+        assert!((10..=20).contains(&values[0]));
+        assert!((0..=5).contains(&values[1]));
+        assert!((1..=28).contains(&values[2]));
+    }
+
+    #[test]
+    fn falls_back_to_the_lowest_value() {
+        let seed = seed(None, true);
+        assert_eq!(seed.resolve("H H * * *").unwrap(), "0 0 * * *");
+        assert_eq!(seed.resolve("H(5-10) * * * *").unwrap(), "5 * * * *");
+        assert_eq!(seed.resolve("H/15 * * * *").unwrap(), "0-59/15 * * * *");
+    }
+
+    #[test]
+    fn requires_a_job_id_without_fallback() {
+        assert!(seed(None, false).resolve("H * * * *").is_err());
+    }
+
+    #[test]
+    fn leaves_names_and_nicknames_alone() {
+        let seed = seed(None, false);
+        assert_eq!(seed.resolve("0 9 * * THU").unwrap(), "0 9 * * THU");
+        assert_eq!(seed.resolve("@hourly").unwrap(), "@hourly");
+    }
+}
@@ -0,0 +1,178 @@
+// `cron_heat(pattern, start, until, bucket)` counts the occurrences in each
+// bucket of a window and normalizes the counts against the busiest bucket,
+// ready for gradient colored heatmaps.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, pattern_parameter,
+    search_limit, timezone_parameter,
+};
+
+// The most buckets a single call may produce.
+const MAX_BUCKETS: i64 = 1_000_000;
+
+#[repr(C)]
+pub(crate) struct HeatBindData {
+    start: DateTime<Tz>,
+    // The length of a bucket in seconds.
+    bucket_seconds: i64,
+    // The number of occurrences in each bucket.
+    counts: *mut Vec<i64>,
+    // The largest of the `counts`.
+    busiest: i64,
+}
+
+impl Free for HeatBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.counts.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.counts));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct HeatInitData {
+    // The index of the next bucket to emit.
+    cursor: usize,
+}
+
+impl Free for HeatInitData {}
+
+// Counts the occurrences in each of the `bucket_count` buckets of
+// `bucket_seconds` starting at `start`, ending the last one at `until`.
+pub(crate) fn bucket_counts(
+    cron: &Cron,
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    bucket_seconds: i64,
+    bucket_count: usize,
+) -> Vec<i64> {
+    let mut counts = vec![0; bucket_count];
+    for x in occurrences_between(cron, start, until) {
+        let bucket = ((x - start).num_seconds() / bucket_seconds) as usize;
+        counts[bucket] += 1;
+    }
+    counts
+}
+
+pub(crate) struct HeatVTab;
+
+impl VTab for HeatVTab {
+    type InitData = HeatInitData;
+    type BindData = HeatBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut HeatBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).counts = null_mut();
+        bind.add_result_column("bucket_start", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("count", LogicalType::new(LogicalTypeId::Bigint));
+        bind.add_result_column("intensity", LogicalType::new(LogicalTypeId::Double));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        if until > search_limit(start) {
+            return Err("The range of cron_heat may span at most five years".into());
+        }
+        let bucket = parse_interval(&bind.get_parameter(3).to_string())?;
+        if bucket < TimeDelta::seconds(1) {
+            return Err("The bucket must be at least one second".into());
+        }
+        let bucket_seconds = bucket.num_seconds();
+
+        // The last bucket may be cut short by `until`.
+        let span = (until - start).num_seconds().max(0);
+        let bucket_count = (span + bucket_seconds - 1) / bucket_seconds;
+        if bucket_count > MAX_BUCKETS {
+            return Err(format!(
+                "cron_heat would produce {} buckets, at most {} are allowed",
+                bucket_count, MAX_BUCKETS
+            )
+            .into());
+        }
+        let counts = bucket_counts(&cron, start, until, bucket_seconds, bucket_count as usize);
+
+        (*data).start = start;
+        (*data).bucket_seconds = bucket_seconds;
+        (*data).busiest = counts.iter().copied().max().unwrap_or(0);
+        (*data).counts = Box::into_raw(Box::new(counts));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut HeatInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).cursor = 0;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<HeatInitData>();
+        let bind_info = func.get_bind_data::<HeatBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let counts = &*(*bind_info).counts;
+        let first = (*init_info).cursor.min(counts.len());
+        let chunk = &counts[first..(first + max_items).min(counts.len())];
+
+        let start = (*bind_info).start.timestamp();
+        let starts: Vec<i64> = (first..first + chunk.len())
+            .map(|i| start + i as i64 * (*bind_info).bucket_seconds)
+            .collect();
+        // A window without occurrences has no busiest bucket to compare with.
+        let busiest = (*bind_info).busiest;
+        let intensities: Vec<f64> = chunk
+            .iter()
+            .map(|&count| {
+                if busiest == 0 {
+                    0.0
+                } else {
+                    count as f64 / busiest as f64
+                }
+            })
+            .collect();
+        output.flat_vector(0).copy(&starts);
+        output.flat_vector(1).copy(chunk);
+        output.flat_vector(2).copy(&intensities);
+        output.set_len(chunk.len());
+        (*init_info).cursor = first + chunk.len();
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Interval),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
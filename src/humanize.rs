@@ -0,0 +1,51 @@
+// Rendering of durations as short English phrases.
+
+use chrono::TimeDelta;
+
+pub(crate) fn plural(count: i64, unit: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, unit)
+    } else {
+        format!("{} {}s", count, unit)
+    }
+}
+
+// Describes how far in the future something is, e.g. "in about 3 hours".
+// Hours and days are rounded to the nearest whole unit.
+pub(crate) fn relative_duration(delta: TimeDelta) -> String {
+    let seconds = delta.num_seconds();
+    if seconds < 60 {
+        return "in under a minute".to_string();
+    }
+    let minutes = (seconds + 30) / 60;
+    if minutes < 60 {
+        return format!("in {}", plural(minutes, "minute"));
+    }
+    let hours = (seconds + 1800) / 3600;
+    if hours < 24 {
+        return format!("in about {}", plural(hours, "hour"));
+    }
+    let days = (seconds + 43200) / 86400;
+    format!("in about {}", plural(days, "day"))
+}
+
+// Writes a countdown with its two most significant units, e.g. "in 1d 2h" or
+// "in 45m 30s", for compact listings.
+pub(crate) fn countdown(delta: TimeDelta) -> String {
+    let seconds = delta.num_seconds().max(0);
+    let units = [
+        (seconds / 86400, "d"),
+        (seconds % 86400 / 3600, "h"),
+        (seconds % 3600 / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let first = units.iter().position(|&(count, _)| count > 0).unwrap_or(3);
+    let parts: Vec<String> = units[first..]
+        .iter()
+        .take(2)
+        .enumerate()
+        .filter(|&(i, &(count, _))| i == 0 || count > 0)
+        .map(|(_, &(count, unit))| format!("{}{}", count, unit))
+        .collect();
+    format!("in {}", parts.join(" "))
+}
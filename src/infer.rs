@@ -0,0 +1,268 @@
+// `cron_pattern_from_times(timestamps)` suggests the simplest pattern firing
+// at a set of observed run times, for codifying schedules recovered from logs.
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeDelta, Timelike};
+use chrono_tz::Tz;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use std::collections::BTreeSet;
+use std::ptr::null_mut;
+
+use crate::params::{parse_list, parse_timestamp_micros};
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, search_limit,
+    timezone_parameter,
+};
+use crate::single_row::OnceInitData;
+use crate::systemd::ranges;
+
+#[repr(C)]
+pub(crate) struct PatternFromTimesBindData {
+    // The suggested pattern, null when no simple pattern fits.
+    pattern: *mut String,
+    // The fraction of the pattern's occurrences from the first to the last
+    // time that were observed.
+    coverage: Option<f64>,
+}
+
+impl Free for PatternFromTimesBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.pattern.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.pattern));
+        }
+    }
+}
+
+// Writes the values observed in a field ranging over `min..=max` as `*`, a
+// step like `*/15` or a list of ranges.
+fn field_text(values: &BTreeSet<u32>, min: u32, max: u32) -> String {
+    let values: Vec<u32> = values.iter().copied().collect();
+    if values.len() as u32 == max - min + 1 {
+        return "*".to_string();
+    }
+    if values.len() >= 2 && values[0] == min {
+        let step = (values[1] - values[0]) as usize;
+        if values == (min..=max).step_by(step).collect::<Vec<u32>>() {
+            return format!("*/{}", step);
+        }
+    }
+    ranges(&values, "-", |x| x.to_string())
+}
+
+// Finds the day fields selecting exactly the observed dates among all those
+// from the first to the last: by weekday if possible, else by day of the
+// month. None when neither does.
+fn day_fields(dates: &BTreeSet<NaiveDate>) -> Option<(String, String)> {
+    let (first, last) = (*dates.first()?, *dates.last()?);
+    let span: Vec<NaiveDate> = first.iter_days().take_while(|&x| x <= last).collect();
+    // Every observed date is selected, so equal counts mean no other is.
+    let selects_exactly = |selected: &dyn Fn(&NaiveDate) -> bool| {
+        span.iter().filter(|&x| selected(x)).count() == dates.len()
+    };
+
+    let weekdays: BTreeSet<u32> = dates
+        .iter()
+        .map(|x| x.weekday().num_days_from_sunday())
+        .collect();
+    if selects_exactly(&|x| weekdays.contains(&x.weekday().num_days_from_sunday())) {
+        return Some(("*".to_string(), field_text(&weekdays, 0, 6)));
+    }
+    let days: BTreeSet<u32> = dates.iter().map(|x| x.day()).collect();
+    if selects_exactly(&|x| days.contains(&x.day())) {
+        return Some((field_text(&days, 1, 31), "*".to_string()));
+    }
+    None
+}
+
+// Suggests a pattern firing at every one of `times`, and the fraction of its
+// occurrences from the first to the last time that were observed.
+//
+// The pattern fires at every observed second, minute and hour combined, on
+// the observed weekdays or days of the month, in every month. Only regular
+// schedules are recognized, None is returned when the dates follow neither
+// the weekdays nor the days of the month.
+pub(crate) fn pattern_from_times(
+    times: &BTreeSet<DateTime<Tz>>,
+) -> Result<Option<(String, f64)>, String> {
+    let dates: BTreeSet<NaiveDate> = times.iter().map(|x| x.date_naive()).collect();
+    let (days, weekdays) = match day_fields(&dates) {
+        Some(fields) => fields,
+        None => return Ok(None),
+    };
+    let seconds: BTreeSet<u32> = times.iter().map(|x| x.second()).collect();
+    let minutes: BTreeSet<u32> = times.iter().map(|x| x.minute()).collect();
+    let hours: BTreeSet<u32> = times.iter().map(|x| x.hour()).collect();
+
+    let mut pattern = format!(
+        "{} {} {} * {}",
+        field_text(&minutes, 0, 59),
+        field_text(&hours, 0, 23),
+        days,
+        weekdays
+    );
+    if seconds.iter().any(|&x| x != 0) {
+        pattern = format!("{} {}", field_text(&seconds, 0, 59), pattern);
+    }
+
+    let cron = parse_pattern_or_error(&pattern)?;
+    let (first, last) = match (times.first(), times.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Ok(None),
+    };
+    if last > search_limit(first) {
+        return Err(
+            "The times given to cron_pattern_from_times may span at most five years".into(),
+        );
+    }
+    let occurrences = occurrences_between(&cron, first, last + TimeDelta::seconds(1)).count();
+    let coverage = times.len() as f64 / occurrences.max(times.len()) as f64;
+    Ok(Some((pattern, coverage)))
+}
+
+pub(crate) struct PatternFromTimesVTab;
+
+impl VTab for PatternFromTimesVTab {
+    type InitData = OnceInitData;
+    type BindData = PatternFromTimesBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut PatternFromTimesBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).pattern = null_mut();
+        (*data).coverage = None;
+        bind.add_result_column("pattern", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("coverage", LogicalType::new(LogicalTypeId::Double));
+
+        // Times are compared to the second, repeated ones count once.
+        let timezone = timezone_parameter(bind)?;
+        let mut times = BTreeSet::new();
+        for text in parse_list(&bind.get_parameter(0).to_string())? {
+            if text == "NULL" {
+                continue;
+            }
+            times.insert(
+                datetime_from_micros(parse_timestamp_micros(&text)?, &timezone)
+                    .ok_or_else(|| format!("Invalid timestamp: {}", text))?,
+            );
+        }
+
+        if let Some((pattern, coverage)) = pattern_from_times(&times)? {
+            (*data).pattern = Box::into_raw(Box::new(pattern));
+            (*data).coverage = Some(coverage);
+        }
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<PatternFromTimesBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        let mut patterns = output.flat_vector(0);
+        let mut coverages = output.flat_vector(1);
+        match ((*bind_info).pattern.as_ref(), (*bind_info).coverage) {
+            (Some(pattern), Some(coverage)) => {
+                patterns.insert(0, pattern.as_str());
+                coverages.as_mut_slice::<f64>()[0] = coverage;
+            }
+            _ => {
+                patterns.set_null(0);
+                coverages.set_null(0);
+            }
+        }
+        output.set_len(1);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::list(&LogicalType::new(
+            LogicalTypeId::Timestamp,
+        ))])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        Tz::UTC
+            .with_ymd_and_hms(2024, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    // Every 15 minutes on the weekdays of June 3 to 14, 2024.
+    fn weekday_log() -> BTreeSet<DateTime<Tz>> {
+        (3..=14)
+            .filter(|&day| !matches!(day, 8 | 9))
+            .flat_map(|day| (0..96).map(move |i| at(6, day, i / 4, i % 4 * 15)))
+            .collect()
+    }
+
+    #[test]
+    fn recognizes_weekday_schedules() {
+        assert_eq!(
+            pattern_from_times(&weekday_log()),
+            Ok(Some(("*/15 * * * 1-5".to_string(), 1.0)))
+        );
+    }
+
+    #[test]
+    fn measures_missed_runs() {
+        let mut times = weekday_log();
+        times.remove(&at(6, 5, 12, 15));
+        assert_eq!(
+            pattern_from_times(&times),
+            Ok(Some(("*/15 * * * 1-5".to_string(), 959.0 / 960.0)))
+        );
+    }
+
+    #[test]
+    fn recognizes_days_of_the_month() {
+        let times: BTreeSet<DateTime<Tz>> = [1, 2, 3]
+            .iter()
+            .flat_map(|&month| [at(month, 1, 2, 30), at(month, 15, 2, 30)])
+            .collect();
+        assert_eq!(
+            pattern_from_times(&times),
+            Ok(Some(("30 2 1,15 * *".to_string(), 1.0)))
+        );
+    }
+
+    #[test]
+    fn irregular_dates_have_no_pattern() {
+        let times: BTreeSet<DateTime<Tz>> = [at(6, 3, 9, 0), at(6, 4, 9, 0), at(7, 5, 9, 0)]
+            .into_iter()
+            .collect();
+        assert_eq!(pattern_from_times(&times), Ok(None));
+        assert_eq!(pattern_from_times(&BTreeSet::new()), Ok(None));
+    }
+}
@@ -1,36 +1,288 @@
 use duckdb::{
-    vtab::{BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab},
+    vtab::{
+        BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId,
+        VTab,
+    },
     Connection, Result,
 };
 
-use chrono::{DateTime, Local, Utc};
-use chrono_tz::Tz;
+use align::Alignment;
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, LocalResult, Months, Offset, TimeDelta, TimeZone,
+    Weekday,
+};
+use chrono_tz::{OffsetName, Tz};
 use croner::Cron;
 use duckdb_loadable_macros::duckdb_entrypoint;
 use ffi::duckdb_vector_size;
+use filters::OccurrenceFilters;
+use hashed::HashSeed;
 use libduckdb_sys as ffi;
+use precision::Precision;
+use shift::{Shift, ShiftMode};
 use std::{
     error::Error,
     ffi::{c_char, c_void},
     ptr::null_mut,
+    time::{Duration, Instant},
 };
 
+mod after;
+mod age;
+mod align;
+mod align_series;
+mod at_index;
+mod bitmap;
+mod business_day;
+mod conflicts;
+mod crontab;
+mod crontab_line;
+mod dense;
+mod density;
+mod describe_delta;
+mod describe_next;
+mod equivalent;
+mod eventbridge;
+mod fields;
+mod filters;
+mod fire_indices;
+mod fires_between;
+mod first_after_gap;
+mod gaps;
+mod hashed;
+mod heat;
+mod humanize;
+mod infer;
+mod matches_local;
+mod membership;
+mod merge_schedules;
+mod missed;
+mod next_across_zones;
+mod next_change;
+mod next_matching;
+mod normalize;
+mod overlap;
+mod overlapping_windows;
+mod params;
+mod parse;
+mod period;
+mod phase;
+mod phrases;
+mod precision;
+mod preview;
+mod schedule;
+mod schedule_hash;
+mod seconds;
+mod shift;
+mod since;
+mod single_row;
+mod spread;
+mod stream;
+mod subset;
+mod summary;
+mod systemd;
+mod timeline;
+mod tz_version;
+mod validate_batch;
+mod weekday_ordinal;
+mod weekly;
+
+// The zone in which `cron()` matches the fields of its patterns.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Zone {
+    Named(Tz),
+    // Occurrences in a fixed offset are generated in UTC on the local wall
+    // clock and shifted back onto the instants they stand for when written.
+    Fixed(FixedOffset),
+}
+
+impl Zone {
+    // The zone the occurrences are generated in.
+    fn generation_zone(self) -> Tz {
+        match self {
+            Zone::Named(timezone) => timezone,
+            Zone::Fixed(_) => Tz::UTC,
+        }
+    }
+
+    // How far the generated occurrences are ahead of the instants they stand
+    // for.
+    fn shift(self) -> TimeDelta {
+        match self {
+            Zone::Named(_) => TimeDelta::zero(),
+            Zone::Fixed(offset) => TimeDelta::seconds(i64::from(offset.local_minus_utc())),
+        }
+    }
+
+    // Converts a DuckDB TIMESTAMP into a bound in the generation zone.
+    fn bound_from_micros(self, micros: i64) -> Option<DateTime<Tz>> {
+        schedule::bound_from_micros(micros, &self.generation_zone()).map(|x| x + self.shift())
+    }
+}
+
+// Resolves the zone from the `timezone`, `utc` and `utc_offset` named
+// parameters.
+fn zone_parameter(bind: &BindInfo) -> Result<Zone, String> {
+    let utc = match bind.get_named_parameter("utc") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+    let offset = bind
+        .get_named_parameter("utc_offset")
+        .map(|value| value.to_string());
+    resolve_zone(
+        schedule::timezone_name(bind).as_deref(),
+        utc,
+        offset.as_deref(),
+    )
+}
+
+// Resolves the zone from a fixed offset from UTC given as an INTERVAL, or
+// else the named zone of the `timezone` and `utc` parameters.
+fn resolve_zone(timezone: Option<&str>, utc: bool, offset: Option<&str>) -> Result<Zone, String> {
+    let offset = match offset {
+        Some(offset) => offset,
+        // `utc` is a shortcut immune to typos in zone names, so giving both
+        // is ambiguous.
+        None if utc && timezone.is_some() => {
+            return Err("utc cannot be combined with an explicit timezone".to_string())
+        }
+        None => return schedule::parse_timezone(timezone).map(Zone::Named),
+    };
+    if timezone.is_some() || utc {
+        return Err("utc_offset cannot be combined with timezone or utc".to_string());
+    }
+    let invalid = || format!("Invalid utc_offset '{}', expected less than a day", offset);
+    let offset = params::parse_interval(offset)?;
+    if offset.subsec_nanos() != 0 {
+        return Err(invalid());
+    }
+    i32::try_from(offset.num_seconds())
+        .ok()
+        .and_then(FixedOffset::east_opt)
+        .map(Zone::Fixed)
+        .ok_or_else(invalid)
+}
+
 #[repr(C)]
 struct CronBindData {
-    // The cron expression.
-    pattern: *mut Cron,
+    // The cron expressions, more than one when the pattern parameter is
+    // split into a union of patterns.
+    patterns: *mut Vec<Cron>,
+    // The position of each of the `patterns` among the split ones, patterns
+    // that never fire being left out.
+    source_indexes: *mut Vec<i32>,
     start: DateTime<chrono_tz::Tz>,
     until: DateTime<chrono_tz::Tz>,
-    timezone: Tz,
+    timezone: Zone,
+    // When set, only validate the parameters and produce no rows.
+    validate_only: bool,
+    // The number of seconds between occurrences for patterns that fire on a
+    // fixed interval, used to skip croner's field matching.
+    fixed_interval: Option<i64>,
+    // When start and until are the same instant, test whether the pattern
+    // fires at exactly that instant instead of returning the next occurrence.
+    point_query: bool,
+    // When start and until are the same instant, treat the window as open at
+    // both ends and return no rows.
+    open_interval: bool,
+    // Emit `start` before the occurrences even if the pattern doesn't fire
+    // then.
+    prime: bool,
+    // Restrictions on the month, hour and weekday of emitted occurrences.
+    filters: OccurrenceFilters,
+    // The precision of the output timestamps.
+    precision: Precision,
+    // How errors in the parameters are handled.
+    on_error: OnError,
+    // The error raised while binding when it is not aborting the query.
+    failure: *mut String,
+    // The windows to generate occurrences for in turn instead of
+    // `start`/`until`, or null.
+    windows: *mut Vec<(DateTime<Tz>, DateTime<Tz>)>,
+    // Skip occurrences already generated for an earlier overlapping window.
+    dedupe_windows: bool,
+    // The most rows to produce. Generation ends at the earlier of this and
+    // `until`.
+    count: Option<usize>,
+    // Fail a scan that produces no rows instead of silently returning none.
+    require_nonempty: bool,
+    // Return a single row of NULLs from a scan that produces no rows.
+    emit_empty_row: bool,
+    // Moves every emitted occurrence, after the window selected it.
+    shift: Option<Shift>,
+    // The longest a chunk spends searching once it has a row, returning what
+    // it has so DuckDB can check for interruption between chunks.
+    yield_interval: Option<Duration>,
+    // The index of the `interval_end` column of `as_intervals`, when
+    // requested.
+    interval_end_column: Option<usize>,
+    // The index of the `error` column of `on_error='null'`.
+    error_column: Option<usize>,
+    // The index of the `window_index` column, when requested.
+    window_index_column: Option<usize>,
+    // The index of the `epoch` column, when requested.
+    epoch_column: Option<usize>,
+    // The index of the first of the `tz_abbrev` and `utc_offset_seconds`
+    // columns, when requested.
+    tz_info_column: Option<usize>,
+    // The index of the `source_index` column, when requested.
+    source_index_column: Option<usize>,
+    // The index of the `seq` column, when requested.
+    seq_column: Option<usize>,
+    // The index of the `dst_anomaly` column, when requested.
+    dst_flag_column: Option<usize>,
+    // The index of the first of the `iso_week`, `quarter`, `day_of_year` and
+    // `day_of_week` columns, when requested.
+    calendar_parts_column: Option<usize>,
+    // Number `day_of_week` from 0 for Sunday instead of from 1 for Monday.
+    unix_weekdays: bool,
+}
+
+// An occurrence and the index of the pattern producing it, None for a primed
+// `start` that no pattern produces.
+type Row = (Option<usize>, DateTime<Tz>);
+
+#[derive(Clone, Copy, PartialEq)]
+enum OnError {
+    // Fail the query.
+    Abort,
+    // Produce no rows.
+    Skip,
+    // Produce a single row with a NULL timestamp and the error message.
+    Null,
+}
+
+impl OnError {
+    fn parse(on_error: &str) -> Result<Self, String> {
+        match on_error {
+            "abort" => Ok(OnError::Abort),
+            "skip" => Ok(OnError::Skip),
+            "null" => Ok(OnError::Null),
+            _ => Err(format!(
+                "Invalid on_error '{}', expected 'abort', 'skip' or 'null'",
+                on_error
+            )),
+        }
+    }
 }
 
 impl Free for CronBindData {
     fn free(&mut self) {
         unsafe {
-            if self.pattern.is_null() {
+            if !self.failure.is_null() {
+                drop(Box::from_raw(self.failure));
+            }
+            if !self.windows.is_null() {
+                drop(Box::from_raw(self.windows));
+            }
+            if !self.source_indexes.is_null() {
+                drop(Box::from_raw(self.source_indexes));
+            }
+            if self.patterns.is_null() {
                 return;
             }
-            drop(Box::from_raw(self.pattern));
+            drop(Box::from_raw(self.patterns));
         }
     }
 }
@@ -38,6 +290,43 @@ impl Free for CronBindData {
 #[repr(C)]
 struct CronInitData {
     done: bool,
+    // The last occurrence emitted, generation resumes strictly after it so
+    // the bind data is never modified by a scan.
+    cursor: Option<DateTime<Tz>>,
+    // The index of the pattern producing the cursor. Coincident occurrences
+    // are all emitted with `with_source_index`, in pattern order, so it
+    // resumes at the cursor for the patterns after it.
+    cursor_pattern: usize,
+    // The number of occurrences emitted by previous chunks.
+    emitted: usize,
+    // The index of the window being generated when there are `windows`.
+    window: usize,
+    // Whether every occurrence of that window has been generated.
+    window_exhausted: bool,
+    // The number of rows written by previous chunks, across all windows.
+    written: usize,
+    // With `as_intervals`, the last row generated, held back until the next
+    // one is known to end its interval.
+    pending: Option<Row>,
+    // Whether the last chunk was cut short by `yield_interval`, so it is not
+    // the end of its window even with fewer rows than requested.
+    yielded: bool,
+}
+
+impl CronInitData {
+    // Starts the scan over, so scanning the same bind data again produces the
+    // same rows.
+    fn reset(&mut self) {
+        self.done = false;
+        self.cursor = None;
+        self.cursor_pattern = 0;
+        self.emitted = 0;
+        self.window = 0;
+        self.window_exhausted = false;
+        self.written = 0;
+        self.pending = None;
+        self.yielded = false;
+    }
 }
 
 struct CronVTab;
@@ -52,58 +341,119 @@ impl VTab for CronVTab {
         bind: &BindInfo,
         data: *mut CronBindData,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).patterns = null_mut();
+        (*data).source_indexes = null_mut();
+        (*data).failure = null_mut();
+        (*data).windows = null_mut();
+
+        (*data).on_error = match bind.get_named_parameter("on_error") {
+            Some(value) => OnError::parse(&value.to_string())?,
+            None => OnError::Abort,
+        };
+        (*data).precision = match bind.get_named_parameter("precision") {
+            Some(value) => Precision::parse(&value.to_string())?,
+            None => Precision::Seconds,
+        };
+        let as_intervals = match bind.get_named_parameter("as_intervals") {
+            Some(value) => value.to_string() == "true",
+            None => false,
+        };
+        let timestamp_type = || LogicalType::new((*data).precision.logical_type_id());
+        let mut columns = 1;
+        (*data).interval_end_column = None;
+        if as_intervals {
+            bind.add_result_column("interval_start", timestamp_type());
+            bind.add_result_column("interval_end", timestamp_type());
+            (*data).interval_end_column = Some(columns);
+            columns += 1;
+        } else {
+            bind.add_result_column("cron", timestamp_type());
+        }
 
-        let pattern = bind.get_parameter(0).to_string();
+        (*data).error_column = None;
+        if (*data).on_error == OnError::Null {
+            bind.add_result_column("error", LogicalType::new(LogicalTypeId::Varchar));
+            (*data).error_column = Some(columns);
+            columns += 1;
+        }
 
-        match Cron::new(&pattern)
-            .with_seconds_optional()
-            .with_dom_and_dow()
-            .parse()
-        {
-            Ok(pattern) => {
-                (*data).pattern = Box::into_raw(Box::new(pattern));
+        (*data).window_index_column = None;
+        if let Some(value) = bind.get_named_parameter("with_window_index") {
+            if value.to_string() == "true" {
+                bind.add_result_column("window_index", LogicalType::new(LogicalTypeId::Integer));
+                (*data).window_index_column = Some(columns);
+                columns += 1;
             }
-            Err(err) => {
-                let error = format!("Failed to parse cron expression: {}", err);
-                (*data).pattern = null_mut();
-                bind.set_error(&error);
+        }
+
+        (*data).epoch_column = None;
+        if let Some(value) = bind.get_named_parameter("with_epoch") {
+            if value.to_string() == "true" {
+                bind.add_result_column("epoch", LogicalType::new(LogicalTypeId::Bigint));
+                (*data).epoch_column = Some(columns);
+                columns += 1;
             }
         }
-        let utc_time: Tz = "UTC".parse().expect("UTC is an expected time zone");
 
-        (*data).timezone = match bind.get_named_parameter("timezone") {
-            Some(timezone) => timezone.to_string().parse().unwrap_or_else(|_| {
-                bind.set_error("Invalid or unknown time zone");
-                utc_time
-            }),
-            None => utc_time,
-        };
+        (*data).tz_info_column = None;
+        if let Some(value) = bind.get_named_parameter("with_tz_info") {
+            if value.to_string() == "true" {
+                bind.add_result_column("tz_abbrev", LogicalType::new(LogicalTypeId::Varchar));
+                bind.add_result_column(
+                    "utc_offset_seconds",
+                    LogicalType::new(LogicalTypeId::Integer),
+                );
+                (*data).tz_info_column = Some(columns);
+                columns += 2;
+            }
+        }
 
-        let now: DateTime<Tz> = Local::now().with_timezone(&(*data).timezone);
-        let now_utc: DateTime<Utc> = Local::now().into();
-        // This isn't getting the proper value, so I'm a big confused.
-        (*data).start = match bind.get_named_parameter("start") {
-            Some(value) => DateTime::from_timestamp(value.to_int64_timestamp() / 1000000, 0)
-                .unwrap_or_else(|| {
-                    bind.set_error("Invalid starting time");
-                    now_utc
-                })
-                .with_timezone(&(*data).timezone),
-            None => now,
-        };
+        (*data).source_index_column = None;
+        if let Some(value) = bind.get_named_parameter("with_source_index") {
+            if value.to_string() == "true" {
+                bind.add_result_column("source_index", LogicalType::new(LogicalTypeId::Integer));
+                (*data).source_index_column = Some(columns);
+                columns += 1;
+            }
+        }
 
-        (*data).until = match bind.get_named_parameter("until") {
-            Some(value) => DateTime::from_timestamp(value.to_int64_timestamp() / 1000000, 0)
-                .unwrap_or_else(|| {
-                    bind.set_error("Invalid until time");
-                    now_utc
-                })
-                .with_timezone(&(*data).timezone),
-            None => now,
-        };
+        (*data).seq_column = None;
+        if let Some(value) = bind.get_named_parameter("with_seq") {
+            if value.to_string() == "true" {
+                bind.add_result_column("seq", LogicalType::new(LogicalTypeId::Integer));
+                (*data).seq_column = Some(columns);
+                columns += 1;
+            }
+        }
 
-        Ok(())
+        (*data).dst_flag_column = None;
+        if let Some(value) = bind.get_named_parameter("with_dst_flag") {
+            if value.to_string() == "true" {
+                bind.add_result_column("dst_anomaly", LogicalType::new(LogicalTypeId::Boolean));
+                (*data).dst_flag_column = Some(columns);
+                columns += 1;
+            }
+        }
+
+        (*data).calendar_parts_column = None;
+        if let Some(value) = bind.get_named_parameter("with_calendar_parts") {
+            if value.to_string() == "true" {
+                bind.add_result_column("iso_week", LogicalType::new(LogicalTypeId::Integer));
+                bind.add_result_column("quarter", LogicalType::new(LogicalTypeId::Integer));
+                bind.add_result_column("day_of_year", LogicalType::new(LogicalTypeId::Integer));
+                bind.add_result_column("day_of_week", LogicalType::new(LogicalTypeId::Integer));
+                (*data).calendar_parts_column = Some(columns);
+            }
+        }
+
+        // Unless aborting, a failure is remembered and reported by func().
+        match bind_parameters(bind, data) {
+            Err(err) if (*data).on_error != OnError::Abort => {
+                (*data).failure = Box::into_raw(Box::new(err.to_string()));
+                Ok(())
+            }
+            result => result,
+        }
     }
 
     unsafe fn init(
@@ -111,7 +461,7 @@ impl VTab for CronVTab {
         data: *mut CronInitData,
     ) -> Result<(), Box<dyn std::error::Error>> {
         unsafe {
-            (*data).done = false;
+            (*data).reset();
         }
         Ok(())
     }
@@ -124,39 +474,136 @@ impl VTab for CronVTab {
         let bind_info = func.get_bind_data::<CronBindData>();
 
         unsafe {
-            let mut vector = output.flat_vector(0);
+            if !(*bind_info).failure.is_null() {
+                // Binding failed without aborting, report it once or not at all.
+                if (*init_info).done || (*bind_info).on_error == OnError::Skip {
+                    output.set_len(0);
+                } else {
+                    for column in 0..output.num_columns() {
+                        output.flat_vector(column).set_null(0);
+                    }
+                    if let Some(column) = (*bind_info).error_column {
+                        output
+                            .flat_vector(column)
+                            .insert(0, (*(*bind_info).failure).as_str());
+                    }
+                    output.set_len(1);
+                }
+                (*init_info).done = true;
+                return Ok(());
+            }
 
-            if (*init_info).done {
-                output.set_len(0)
+            let mut window = None;
+            let rows = if (*init_info).done || (*bind_info).validate_only {
+                Vec::new()
+            } else if !(*bind_info).windows.is_null() {
+                let max_items: usize = duckdb_vector_size().try_into()?;
+                let rows = next_window_chunk(bind_info, init_info, max_items);
+                window = Some((*init_info).window);
+                rows
+            } else if let Some(rows) = collapsed_rows(
+                &*(*bind_info).patterns,
+                (*bind_info).start,
+                (*bind_info).until,
+                (*bind_info).open_interval,
+                (*bind_info).point_query,
+                (*bind_info).source_index_column.is_some(),
+            )? {
+                (*init_info).done = true;
+                rows
             } else {
                 // DuckDB has a limit to its vector size, respect it.
-                let max_items: usize = duckdb_vector_size().try_into().unwrap();
-                let mut item_count: usize = 0;
-
-                let timestamps: Vec<i64> = (*(*bind_info).pattern)
-                    .iter_from((*bind_info).start)
-                    .take_while(|&x| {
-                        if ((*bind_info).start == (*bind_info).until && item_count == 0)
-                            || (x <= (*bind_info).until && item_count < max_items)
-                        {
-                            item_count += 1;
-                            (*bind_info).start = x;
-                            true
-                        } else {
-                            false
-                        }
-                    })
-                    .map(|x| x.timestamp())
-                    .collect::<Vec<i64>>();
-
-                output.set_len(timestamps.len());
-
-                vector.copy(&timestamps);
-
-                // If the number of timestamps produced is less than the max_items
-                // it means that the until limit has been reached.
-                (*init_info).done = timestamps.len() < max_items;
+                let max_items: usize = duckdb_vector_size().try_into()?;
+                let (start, until) = ((*bind_info).start, (*bind_info).until);
+                let primed = (*bind_info).prime
+                    && (*init_info).cursor.is_none()
+                    && (*init_info).emitted == 0;
+                let (rows, done) = range_chunk(
+                    |requested| {
+                        let rows = next_chunk(bind_info, init_info, start, until, requested);
+                        (rows, (*init_info).yielded)
+                    },
+                    start,
+                    primed,
+                    max_items,
+                );
+                (*init_info).done = done;
+                rows
+            };
+
+            // Each interval ends at the next row, so the last row of a chunk
+            // is held back until the next chunk, and the last one of the scan
+            // ends at `until`.
+            let mut ends = Vec::new();
+            let rows = if (*bind_info).interval_end_column.is_some() {
+                let mut rows: Vec<Row> = (*init_info)
+                    .pending
+                    .take()
+                    .into_iter()
+                    .chain(rows)
+                    .collect();
+                if !(*init_info).done {
+                    (*init_info).pending = rows.pop();
+                }
+                let last_end = match (*init_info).pending {
+                    Some((_, next)) => next,
+                    None => (*bind_info).until,
+                };
+                ends = rows.iter().skip(1).map(|&(_, x)| x).collect();
+                if let Some(&(_, last)) = rows.last() {
+                    ends.push(last_end.max(last));
+                }
+                rows
+            } else {
+                rows
+            };
+
+            // An empty chunk ends the scan, so an empty first chunk means the
+            // pattern never fired.
+            if (*bind_info).require_nonempty
+                && !(*bind_info).validate_only
+                && rows.is_empty()
+                && (*init_info).written == 0
+            {
+                return Err(format!(
+                    "The cron pattern produced no occurrences between {} and {}, \
+                     it may never fire in this window",
+                    (*bind_info).start,
+                    (*bind_info).until
+                )
+                .into());
             }
+            if (*bind_info).emit_empty_row
+                && !(*bind_info).validate_only
+                && rows.is_empty()
+                && (*init_info).written == 0
+            {
+                for column in 0..output.num_columns() {
+                    output.flat_vector(column).set_null(0);
+                }
+                output.set_len(1);
+                (*init_info).written = 1;
+                (*init_info).done = true;
+                return Ok(());
+            }
+
+            // Whichever of `count` and `until` is reached first ends the scan.
+            let mut rows = rows;
+            if let Some(count) = (*bind_info).count {
+                if truncate_to_count(&mut rows, count, (*init_info).written) {
+                    (*init_info).done = true;
+                }
+            }
+
+            write_rows(
+                bind_info,
+                output,
+                &rows,
+                &ends,
+                window,
+                (*init_info).written,
+            )?;
+            (*init_info).written += rows.len();
         }
         Ok(())
     }
@@ -168,6 +615,103 @@ impl VTab for CronVTab {
 
     fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
         Some(vec![
+            (
+                "align_to".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "as_intervals".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            ("count".to_string(), LogicalType::new(LogicalTypeId::Bigint)),
+            (
+                "dedupe_windows".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "dom_dow".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "dow_numbering".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "field_order".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "hash_fallback".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "day_values".to_string(),
+                LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+            ),
+            (
+                "hour_in".to_string(),
+                LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+            ),
+            (
+                "hour_values".to_string(),
+                LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+            ),
+            (
+                "max_horizon".to_string(),
+                LogicalType::new(LogicalTypeId::Interval),
+            ),
+            (
+                "minute_values".to_string(),
+                LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+            ),
+            (
+                "month_in".to_string(),
+                LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+            ),
+            (
+                "weekday_in".to_string(),
+                LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+            ),
+            (
+                "from_month_end".to_string(),
+                LogicalType::new(LogicalTypeId::Integer),
+            ),
+            (
+                "business_days".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "job_id".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            ("nl".to_string(), LogicalType::new(LogicalTypeId::Boolean)),
+            (
+                "on_error".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "open_interval".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "point_query".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "prime".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "precision".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "range".to_string(),
+                LogicalType::struct_type(&[
+                    ("start", LogicalType::new(LogicalTypeId::Timestamp)),
+                    ("until", LogicalType::new(LogicalTypeId::Timestamp)),
+                ]),
+            ),
             (
                 "start".to_string(),
                 LogicalType::new(LogicalTypeId::Timestamp),
@@ -180,15 +724,1524 @@ impl VTab for CronVTab {
                 "timezone".to_string(),
                 LogicalType::new(LogicalTypeId::Varchar),
             ),
+            ("utc".to_string(), LogicalType::new(LogicalTypeId::Boolean)),
+            (
+                "utc_offset".to_string(),
+                LogicalType::new(LogicalTypeId::Interval),
+            ),
+            (
+                "emit_empty_row".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "require_nonempty".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "shift".to_string(),
+                LogicalType::new(LogicalTypeId::Interval),
+            ),
+            (
+                "shift_mode".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "split".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "strict".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "strict_field_ranges".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "strict_range".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "strict_seconds_zero".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "windows".to_string(),
+                LogicalType::list(&LogicalType::struct_type(&[
+                    ("start", LogicalType::new(LogicalTypeId::Timestamp)),
+                    ("until", LogicalType::new(LogicalTypeId::Timestamp)),
+                ])),
+            ),
+            (
+                "with_window_index".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "with_epoch".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "with_tz_info".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "with_source_index".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "with_seq".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "with_calendar_parts".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "with_dst_flag".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "validate_only".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "week_starts_on".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "yield_interval_us".to_string(),
+                LogicalType::new(LogicalTypeId::Bigint),
+            ),
         ])
     }
 }
 
+// What `next_chunk` does with a candidate occurrence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Decision {
+    // Return it.
+    Emit,
+    // Pass over it, it is rejected by the filters.
+    Skip,
+    // End the chunk.
+    Stop,
+}
+
+// Decides what happens to a candidate occurrence of a window ending at
+// `until`, given the number of occurrences `emitted` by earlier chunks and
+// the `item_count` of this one.
+//
+// `until` is exclusive. A collapsed window, whose start and end are the same
+// instant, still returns its first occurrence passing the filters, but only
+// searches for it up to `collapsed_limit`.
+fn should_emit(
+    candidate: DateTime<Tz>,
+    passes_filters: bool,
+    until: DateTime<Tz>,
+    collapsed_limit: Option<DateTime<Tz>>,
+    emitted: usize,
+    item_count: usize,
+    max_items: usize,
+) -> Decision {
+    let within = match collapsed_limit {
+        Some(limit) if candidate >= until => emitted + item_count == 0 && candidate <= limit,
+        _ => candidate < until && item_count < max_items,
+    };
+    if !within {
+        Decision::Stop
+    } else if passes_filters {
+        Decision::Emit
+    } else {
+        Decision::Skip
+    }
+}
+
+// The rows of a collapsed window, whose start and end are the same instant,
+// when `open_interval` or `point_query` settle it without generating
+// occurrences. None when the window is generated as usual.
+//
+// Nothing lies strictly between an instant and itself. A point query
+// produces at most one row, the instant itself, or one per matching pattern
+// with `every_source`.
+fn collapsed_rows(
+    patterns: &[Cron],
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    open_interval: bool,
+    point_query: bool,
+    every_source: bool,
+) -> Result<Option<Vec<Row>>, croner::errors::CronError> {
+    if start != until {
+        return Ok(None);
+    }
+    if open_interval {
+        return Ok(Some(Vec::new()));
+    }
+    if !point_query {
+        return Ok(None);
+    }
+    let mut rows = Vec::new();
+    for (i, pattern) in patterns.iter().enumerate() {
+        if pattern.is_time_matching(&start)? {
+            rows.push((Some(i), start));
+        }
+    }
+    if !every_source {
+        rows.truncate(1);
+    }
+    Ok(Some(rows))
+}
+
+// Collects the rows of a chunk from the candidate occurrences, pulling
+// candidates only until `should_emit` stops, so a chunk of a long window
+// generates no more than it returns.
+//
+// With `yield_after`, the chunk also ends once it holds that many rows and
+// the instant has passed, and the second value returned is true.
+fn collect_chunk(
+    occurrences: impl Iterator<Item = (usize, DateTime<Tz>)>,
+    passes_filters: impl Fn(&DateTime<Tz>) -> bool,
+    until: DateTime<Tz>,
+    collapsed_limit: Option<DateTime<Tz>>,
+    emitted: usize,
+    max_items: usize,
+    yield_after: Option<(usize, Instant)>,
+) -> (Vec<Row>, bool) {
+    let mut rows: Vec<Row> = Vec::new();
+    for (i, x) in occurrences {
+        match should_emit(
+            x,
+            passes_filters(&x),
+            until,
+            collapsed_limit,
+            emitted,
+            rows.len(),
+            max_items,
+        ) {
+            Decision::Emit => rows.push((Some(i), x)),
+            Decision::Skip => {}
+            Decision::Stop => break,
+        }
+        if let Some((min_rows, deadline)) = yield_after {
+            if rows.len() >= min_rows && Instant::now() >= deadline {
+                return (rows, true);
+            }
+        }
+    }
+    (rows, false)
+}
+
+// Truncates a chunk to the rows left of `count` once `written` rows have
+// been, returning whether the count is reached and the scan ends.
+fn truncate_to_count(rows: &mut Vec<Row>, count: usize, written: usize) -> bool {
+    rows.truncate(count.saturating_sub(written));
+    written + rows.len() >= count
+}
+
+// Generates up to `max_items` occurrences in the window from `start` to
+// `until` following the scan's cursor.
+unsafe fn next_chunk(
+    bind: *const CronBindData,
+    init: *mut CronInitData,
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    max_items: usize,
+) -> Vec<Row> {
+    let patterns = &*(*bind).patterns;
+    let every_source = (*bind).source_index_column.is_some();
+    let next_occurrences = || -> Box<dyn Iterator<Item = (usize, DateTime<Tz>)>> {
+        let streams = patterns
+            .iter()
+            .enumerate()
+            .map(|(i, pattern)| -> Box<dyn Iterator<Item = DateTime<Tz>>> {
+                match (*init).cursor {
+                    Some(cursor) if every_source && i > (*init).cursor_pattern => {
+                        Box::new(pattern.iter_from(cursor))
+                    }
+                    Some(cursor) => Box::new(pattern.iter_after(cursor)),
+                    None => Box::new(pattern.iter_from(start)),
+                }
+            })
+            .collect();
+        let merged = schedule::merge_occurrences(streams);
+        if every_source {
+            Box::new(merged)
+        } else {
+            // Patterns firing at the same instant produce a single occurrence.
+            Box::new(schedule::dedup_sorted(merged))
+        }
+    };
+    let occurrences: Box<dyn Iterator<Item = (usize, DateTime<Tz>)>> = match (*bind).fixed_interval
+    {
+        // There is a single pattern.
+        Some(seconds) => {
+            let step = TimeDelta::seconds(seconds);
+            Box::new(std::iter::successors(
+                next_occurrences().next(),
+                move |&(i, x)| Some((i, x + step)),
+            ))
+        }
+        None => next_occurrences(),
+    };
+
+    // The filters may reject every candidate, so bound how far the
+    // collapsed window case may search for one that passes.
+    // Only the single window of `start` and `until` may be collapsed.
+    let collapsed = (*bind).windows.is_null() && start == until;
+    let collapsed_limit = collapsed.then(|| schedule::search_limit(until));
+    let filters = (*bind).filters;
+    let emitted = (*init).emitted;
+
+    // An empty chunk ends the scan, so a chunk only yields once it has a row
+    // to return, two with `as_intervals` since the last one is held back.
+    let deadline = (*bind).yield_interval.map(|x| Instant::now() + x);
+    let min_rows = if (*bind).interval_end_column.is_some() && (*init).pending.is_none() {
+        2
+    } else {
+        1
+    };
+
+    let (rows, yielded) = collect_chunk(
+        occurrences,
+        |x| filters.matches(x),
+        until,
+        collapsed_limit,
+        emitted,
+        max_items,
+        deadline.map(|x| (min_rows, x)),
+    );
+    (*init).yielded = yielded;
+
+    if let Some(&(i, last)) = rows.last() {
+        (*init).cursor = Some(last);
+        (*init).cursor_pattern = i.unwrap_or(0);
+    }
+    (*init).emitted += rows.len();
+    rows
+}
+
+// Generates the next chunk of the window of `start` and `until`, given a
+// `next_chunk` producing up to the requested number of occurrences and
+// whether it yielded before finishing. Returns the rows and whether the scan
+// is done.
+//
+// If the number of timestamps produced is less than requested it means that
+// the until limit has been reached. When the occurrences fill exactly a whole
+// number of chunks, the next call finds none and its empty chunk ends the
+// scan, so no extra row is emitted and none is lost.
+fn range_chunk(
+    next_chunk: impl FnOnce(usize) -> (Vec<Row>, bool),
+    start: DateTime<Tz>,
+    primed: bool,
+    max_items: usize,
+) -> (Vec<Row>, bool) {
+    // The primed row takes a place in the first chunk.
+    let requested = if primed { max_items - 1 } else { max_items };
+    let (mut rows, yielded) = next_chunk(requested);
+    let done = rows.len() < requested && !yielded;
+    if primed && rows.first().map(|&(_, x)| x) != Some(start) {
+        rows.insert(0, (None, start));
+    }
+    (rows, done)
+}
+
+// Generates the next chunk of occurrences for the `windows`, in turn.
+//
+// A chunk only holds occurrences of the window in `init.window`. An empty
+// chunk ends the scan, so windows without any are skipped over.
+unsafe fn next_window_chunk(
+    bind: *const CronBindData,
+    init: *mut CronInitData,
+    max_items: usize,
+) -> Vec<Row> {
+    let windows = &*(*bind).windows;
+    while (*init).window < windows.len() {
+        if !(*init).window_exhausted {
+            let (start, until) = windows[(*init).window];
+            let mut rows = next_chunk(bind, init, start, until, max_items);
+            (*init).window_exhausted = rows.len() < max_items && !(*init).yielded;
+            if (*bind).dedupe_windows {
+                let earlier = &windows[..(*init).window];
+                rows.retain(|(_, x)| !earlier.iter().any(|(s, u)| s <= x && x < u));
+            }
+            if !rows.is_empty() {
+                return rows;
+            }
+            if !(*init).window_exhausted {
+                continue;
+            }
+        }
+        (*init).window += 1;
+        (*init).window_exhausted = false;
+        (*init).cursor = None;
+        (*init).cursor_pattern = 0;
+        (*init).emitted = 0;
+    }
+    (*init).done = true;
+    Vec::new()
+}
+
+// Writes the occurrences and any additional columns into the output chunk.
+unsafe fn write_rows(
+    bind: *const CronBindData,
+    output: &mut DataChunk,
+    tagged: &[Row],
+    ends: &[DateTime<Tz>],
+    window: Option<usize>,
+    written: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Every chunk is sized to fit a vector, writing past one would corrupt
+    // memory, so a mistake in that bookkeeping fails the query instead.
+    let capacity: usize = duckdb_vector_size().try_into()?;
+    if tagged.len() > capacity {
+        return Err(format!(
+            "Generated {} rows for an output vector of {} rows",
+            tagged.len(),
+            capacity
+        )
+        .into());
+    }
+
+    // Every column describes the shifted occurrence, except whether the
+    // occurrence itself is a daylight saving anomaly.
+    let occurrences = tagged;
+    let (tagged, ends): (Vec<Row>, Vec<DateTime<Tz>>) = match (*bind).shift {
+        Some(shift) => (
+            tagged.iter().map(|&(i, x)| (i, shift.apply(x))).collect(),
+            ends.iter().map(|&x| shift.apply(x)).collect(),
+        ),
+        None => (tagged.to_vec(), ends.to_vec()),
+    };
+
+    // Occurrences in a fixed offset are shifted back onto their instants.
+    let zone = (*bind).timezone;
+    let rows: Vec<DateTime<Tz>> = tagged.iter().map(|&(_, x)| x - zone.shift()).collect();
+
+    let precision = (*bind).precision;
+    let timestamps = rows
+        .iter()
+        .map(|x| precision.convert(x))
+        .collect::<Result<Vec<i64>, String>>()?;
+    output.flat_vector(0).copy(&timestamps);
+
+    if let Some(column) = (*bind).interval_end_column {
+        let ends = ends
+            .iter()
+            .map(|&x| precision.convert(&(x - zone.shift())))
+            .collect::<Result<Vec<i64>, String>>()?;
+        output.flat_vector(column).copy(&ends);
+    }
+
+    if let Some(column) = (*bind).error_column {
+        let mut errors = output.flat_vector(column);
+        for row in 0..rows.len() {
+            errors.set_null(row);
+        }
+    }
+
+    if let Some(column) = (*bind).window_index_column {
+        let mut indexes = output.flat_vector(column);
+        let indexes = indexes.as_mut_slice::<i32>();
+        for index in indexes.iter_mut().take(rows.len()) {
+            *index = window.unwrap_or(0) as i32;
+        }
+    }
+
+    if let Some(column) = (*bind).epoch_column {
+        let epochs: Vec<i64> = rows.iter().map(|x| x.timestamp()).collect();
+        output.flat_vector(column).copy(&epochs);
+    }
+
+    if let Some(column) = (*bind).tz_info_column {
+        let mut abbreviations = output.flat_vector(column);
+        let mut offsets = output.flat_vector(column + 1);
+        let offsets = offsets.as_mut_slice::<i32>();
+        for (row, x) in rows.iter().enumerate() {
+            match zone {
+                Zone::Named(_) => {
+                    abbreviations.insert(row, x.offset().abbreviation());
+                    offsets[row] = x.offset().fix().local_minus_utc();
+                }
+                Zone::Fixed(offset) => {
+                    abbreviations.insert(row, offset.to_string().as_str());
+                    offsets[row] = offset.local_minus_utc();
+                }
+            }
+        }
+    }
+
+    if let Some(column) = (*bind).source_index_column {
+        let source_indexes = &*(*bind).source_indexes;
+        let mut sources = output.flat_vector(column);
+        for (row, &(i, _)) in tagged.iter().enumerate() {
+            match i {
+                Some(i) => sources.as_mut_slice::<i32>()[row] = source_indexes[i],
+                None => sources.set_null(row),
+            }
+        }
+    }
+
+    if let Some(column) = (*bind).seq_column {
+        let mut seqs = output.flat_vector(column);
+        let seqs = seqs.as_mut_slice::<i32>();
+        for (row, seq) in seqs.iter_mut().take(rows.len()).enumerate() {
+            *seq = i32::try_from(written + row)?;
+        }
+    }
+
+    if let Some(column) = (*bind).dst_flag_column {
+        let patterns = &*(*bind).patterns;
+        let mut flags = output.flat_vector(column);
+        let flags = flags.as_mut_slice::<bool>();
+        for (row, &(i, x)) in occurrences.iter().enumerate() {
+            flags[row] = dst_anomaly(i.map(|i| &patterns[i]), x);
+        }
+    }
+
+    // The parts are those of the local time, which is what occurrences in a
+    // fixed offset hold before being shifted back.
+    if let Some(column) = (*bind).calendar_parts_column {
+        let mut weeks = output.flat_vector(column);
+        let mut quarters = output.flat_vector(column + 1);
+        let mut days = output.flat_vector(column + 2);
+        let mut weekdays = output.flat_vector(column + 3);
+        let weeks = weeks.as_mut_slice::<i32>();
+        let quarters = quarters.as_mut_slice::<i32>();
+        let days = days.as_mut_slice::<i32>();
+        let weekdays = weekdays.as_mut_slice::<i32>();
+        for (row, &(_, x)) in tagged.iter().enumerate() {
+            weeks[row] = x.iso_week().week() as i32;
+            quarters[row] = x.month0() as i32 / 3 + 1;
+            days[row] = x.ordinal() as i32;
+            weekdays[row] = weekday_number(x.weekday(), (*bind).unix_weekdays);
+        }
+    }
+
+    output.set_len(rows.len());
+    Ok(())
+}
+
+// Whether an occurrence is affected by a daylight saving transition: its
+// local time happens twice, or the pattern's time doesn't exist that day and
+// croner moved the occurrence to a local time the pattern doesn't match.
+fn dst_anomaly(pattern: Option<&Cron>, x: DateTime<Tz>) -> bool {
+    let ambiguous = matches!(
+        x.timezone().from_local_datetime(&x.naive_local()),
+        LocalResult::Ambiguous(_, _)
+    );
+    let moved = pattern.is_some_and(|pattern| !pattern.is_time_matching(&x).unwrap_or(true));
+    ambiguous || moved
+}
+
+// The end of a horizon of `months` calendar months plus `fixed` after
+// `from`. Months move the local wall clock, the way `INTERVAL 1 YEAR` does
+// in DuckDB; a time that doesn't exist in the zone that day falls back to
+// moving the UTC clock. None when the end is beyond chrono's range.
+fn horizon_end(from: DateTime<Tz>, months: u32, fixed: TimeDelta) -> Option<DateTime<Tz>> {
+    let months = Months::new(months);
+    let zone = from.timezone();
+    let moved = from
+        .naive_local()
+        .checked_add_months(months)
+        .and_then(|x| zone.from_local_datetime(&x).earliest())
+        .or_else(|| {
+            from.naive_utc()
+                .checked_add_months(months)
+                .map(|x| zone.from_utc_datetime(&x))
+        })?;
+    moved.checked_add_signed(fixed)
+}
+
+// Rejects a pattern firing at a second other than 0, for `strict_seconds_zero`.
+// Five field patterns always fire at second 0.
+fn check_seconds_zero(pattern: &str) -> Result<(), String> {
+    let seconds = &fields::split_fields(pattern)?[0];
+    let field = fields::parse_field(fields::FieldKind::Seconds, seconds)?;
+    if field.wildcard || !field.specials.is_empty() || field.values != [0] {
+        return Err(format!(
+            "The cron expression '{}' fires at second '{}', but strict_seconds_zero \
+             only allows 0",
+            pattern, seconds
+        ));
+    }
+    Ok(())
+}
+
+// Numbers a weekday for the calendar parts output: ISO numbers Monday 1 to
+// Sunday 7, unix numbers Sunday 0 to Saturday 6.
+fn weekday_number(weekday: Weekday, unix: bool) -> i32 {
+    if unix {
+        weekday.num_days_from_sunday() as i32
+    } else {
+        weekday.number_from_monday() as i32
+    }
+}
+
+// Reconciles the start time given positionally to `cron_from()` with the
+// named `start` parameter, which cannot both be given.
+fn start_parameter(positional: Option<i64>, named: Option<i64>) -> Result<Option<i64>, String> {
+    match (positional, named) {
+        (Some(_), Some(_)) => Err(
+            "The start time cannot be given both positionally and as a named parameter".to_string(),
+        ),
+        (positional, named) => Ok(positional.or(named)),
+    }
+}
+
+// Parses the pattern and the named parameters of `cron()` into the bind data.
+unsafe fn bind_parameters(
+    bind: &BindInfo,
+    data: *mut CronBindData,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pattern = schedule::pattern_parameter(bind, 0)?;
+
+    // A single string may hold several patterns forming a union.
+    let sources: Vec<&str> = match bind.get_named_parameter("split") {
+        Some(separator) => {
+            let separator = separator.to_string();
+            if separator.is_empty() {
+                return Err("The split separator must not be empty".into());
+            }
+            pattern
+                .split(separator.as_str())
+                .map(str::trim)
+                .filter(|x| !x.is_empty())
+                .collect()
+        }
+        None => vec![pattern.as_str()],
+    };
+    if sources.is_empty() {
+        return Err("No cron patterns were found after splitting".into());
+    }
+
+    // Phrases like `every weekday at 9am` are compiled into patterns first.
+    let natural_language = match bind.get_named_parameter("nl") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+    // Some legacy systems write the seconds field last.
+    let seconds_last = match bind.get_named_parameter("field_order") {
+        Some(value) => match value.to_string().as_str() {
+            "standard" => false,
+            "seconds_last" => true,
+            other => {
+                return Err(format!(
+                    "Invalid field_order '{}', expected 'standard' or 'seconds_last'",
+                    other
+                )
+                .into())
+            }
+        },
+        None => false,
+    };
+    let seed = HashSeed::from_bind(bind)?;
+    let sources = sources
+        .iter()
+        .map(|&source| {
+            if natural_language {
+                seed.resolve(&phrases::compile(source)?)
+            } else if seconds_last {
+                seed.resolve(&fields::seconds_first(source)?)
+            } else {
+                seed.resolve(source)
+            }
+        })
+        .collect::<Result<Vec<String>, String>>()?;
+
+    // `from_month_end` picks the day itself, the patterns only give the
+    // times of day.
+    let sources = if bind.get_named_parameter("from_month_end").is_some() {
+        sources
+            .iter()
+            .map(|source| fields::any_day(source))
+            .collect::<Result<Vec<String>, String>>()?
+    } else {
+        sources
+    };
+
+    // Explicit value lists replace the corresponding field of every pattern.
+    let mut sources = sources;
+    for (name, kind) in [
+        ("minute_values", fields::FieldKind::Minutes),
+        ("hour_values", fields::FieldKind::Hours),
+        ("day_values", fields::FieldKind::DaysOfMonth),
+    ] {
+        let value = match bind.get_named_parameter(name) {
+            Some(value) => value,
+            None => continue,
+        };
+        if kind == fields::FieldKind::DaysOfMonth
+            && bind.get_named_parameter("from_month_end").is_some()
+        {
+            return Err("day_values cannot be combined with from_month_end".into());
+        }
+        let mut values = Vec::new();
+        for x in params::parse_int_list(&value.to_string())? {
+            let (min, max) = (kind.min(), kind.max());
+            match u32::try_from(x) {
+                Ok(x) if (min..=max).contains(&x) => values.push(x),
+                _ => {
+                    return Err(
+                        format!("{} value {} is out of range {}-{}", name, x, min, max).into(),
+                    )
+                }
+            }
+        }
+        if values.is_empty() {
+            return Err(format!("{} must not be empty", name).into());
+        }
+        values.sort_unstable();
+        values.dedup();
+        sources = sources
+            .iter()
+            .map(|source| fields::with_values(source, kind, &values))
+            .collect::<Result<Vec<String>, String>>()?;
+    }
+
+    // There is no way to surface a warning through DuckDB, so an
+    // ambiguous pattern can only be rejected when asked to be strict.
+    let strict = match bind.get_named_parameter("strict") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    // croner reports out of range values as a generic parse failure, the
+    // field expansion names the field and its range.
+    let strict_field_ranges = match bind.get_named_parameter("strict_field_ranges") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    // A policy guard for minute granular schedules, which may still write a
+    // seconds field as long as it is 0.
+    let strict_seconds_zero = match bind.get_named_parameter("strict_seconds_zero") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    let days = match bind.get_named_parameter("dom_dow") {
+        Some(value) => schedule::DayCombination::parse(&value.to_string())?,
+        None => schedule::DayCombination::And,
+    };
+
+    let mut patterns = Vec::with_capacity(sources.len());
+    let mut source_indexes = Vec::with_capacity(sources.len());
+    for (i, source) in sources.iter().enumerate() {
+        if strict {
+            if let Some(ambiguity) = schedule::field_count_ambiguity(source) {
+                return Err(ambiguity.into());
+            }
+        }
+        if strict_seconds_zero {
+            check_seconds_zero(source)?;
+        }
+        if strict_field_ranges {
+            fields::parse_fields(source)
+                .map_err(|err| format!("Invalid cron expression '{}': {}", source, err))?;
+        }
+        let parsed = schedule::parse_pattern_combining(source, days)
+            .map_err(|err| format!("Failed to parse cron expression '{}': {}", source, err))?;
+        // A pattern whose dates never exist contributes nothing, and searching
+        // for its next occurrence would scan far beyond `until`.
+        if !schedule::never_fires_combining(source, days) {
+            patterns.push(parsed);
+            source_indexes.push(i32::try_from(i)?);
+        }
+    }
+    (*data).patterns = Box::into_raw(Box::new(patterns));
+    (*data).source_indexes = Box::into_raw(Box::new(source_indexes));
+    (*data).timezone = zone_parameter(bind)?;
+    let zone = (*data).timezone;
+
+    let now: DateTime<Tz> = Local::now().with_timezone(&zone.generation_zone()) + zone.shift();
+
+    // The bounds are given individually or together as a range struct.
+    // `cron_from()` takes the start time positionally.
+    let positional_start = if bind.get_parameter_count() > 1 {
+        let value = bind.get_parameter(1);
+        match value.to_string().as_str() {
+            "NULL" => None,
+            _ => Some(value.to_int64_timestamp()),
+        }
+    } else {
+        None
+    };
+    let mut start = start_parameter(
+        positional_start,
+        bind.get_named_parameter("start")
+            .map(|value| value.to_int64_timestamp()),
+    )?;
+    let mut until = bind
+        .get_named_parameter("until")
+        .map(|value| value.to_int64_timestamp());
+    if let Some(range) = bind.get_named_parameter("range") {
+        if start.is_some() || until.is_some() {
+            return Err("The range parameter cannot be combined with start or until".into());
+        }
+        for (name, value) in params::parse_struct(&range.to_string())? {
+            let bound = match value.as_str() {
+                "NULL" => None,
+                _ => Some(params::parse_timestamp_micros(&value)?),
+            };
+            match name.as_str() {
+                "start" => start = bound,
+                "until" => until = bound,
+                _ => return Err(format!("Unknown range field: {}", name).into()),
+            }
+        }
+    }
+
+    // Several windows may be given instead, generated one after the other.
+    let mut windows = None;
+    if let Some(value) = bind.get_named_parameter("windows") {
+        if start.is_some() || until.is_some() {
+            return Err(
+                "The windows parameter cannot be combined with start, until or range".into(),
+            );
+        }
+        if (*data).interval_end_column.is_some() {
+            return Err("The windows parameter cannot be combined with as_intervals".into());
+        }
+        let mut bounds = Vec::new();
+        for window in params::parse_list(&value.to_string())? {
+            let (mut window_start, mut window_until) = (None, None);
+            for (name, value) in params::parse_struct(&window)? {
+                let bound = zone.bound_from_micros(params::parse_timestamp_micros(&value)?);
+                match name.as_str() {
+                    "start" => window_start = bound,
+                    "until" => window_until = bound,
+                    _ => return Err(format!("Unknown window field: {}", name).into()),
+                }
+            }
+            match (window_start, window_until) {
+                (Some(start), Some(until)) => bounds.push((start, until)),
+                _ => return Err(format!("Every window needs a start and until: {}", window).into()),
+            }
+        }
+        windows = Some(bounds);
+    }
+    (*data).dedupe_windows = match bind.get_named_parameter("dedupe_windows") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    // Missing bounds default to now, which quietly returns a single row when
+    // a bound was forgotten. Strict pipelines can ask for an error instead.
+    let strict_range = match bind.get_named_parameter("strict_range") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+    if strict_range && windows.is_none() && (start.is_none() || until.is_none()) {
+        return Err("strict_range requires both start and until to be given".into());
+    }
+
+    // TIMESTAMP bounds are UTC instants. Viewing them in the configured zone
+    // doesn't move them, so comparing occurrences with them compares absolute
+    // instants even across daylight saving transitions.
+    (*data).start = match start {
+        Some(value) => zone
+            .bound_from_micros(value)
+            .ok_or("Invalid starting time")?,
+        None => now,
+    };
+
+    (*data).count = match bind.get_named_parameter("count") {
+        Some(value) => {
+            Some(usize::try_from(value.to_int64()).map_err(|_| "count cannot be negative")?)
+        }
+        None => None,
+    };
+    if (*data).count.is_some() && (*data).interval_end_column.is_some() {
+        return Err("count cannot be combined with as_intervals".into());
+    }
+
+    // A count alone generates that many occurrences, as far as the search
+    // horizon allows.
+    (*data).until = match until {
+        Some(value) => zone.bound_from_micros(value).ok_or("Invalid until time")?,
+        None if (*data).count.is_some() => schedule::search_limit((*data).start),
+        None => now,
+    };
+
+    // Iteration may begin at the start of the period containing `start`, in
+    // the configured zone, instead of at `start` itself.
+    if let Some(value) = bind.get_named_parameter("align_to") {
+        let alignment = Alignment::parse(&value.to_string())?;
+        (*data).start = alignment.truncate((*data).start);
+        if let Some(windows) = windows.as_mut() {
+            for (start, _) in windows.iter_mut() {
+                *start = alignment.truncate(*start);
+            }
+        }
+    }
+
+    // A safety net for very distant or mistaken bounds, in wall-clock terms.
+    if let Some(horizon) = bind.get_named_parameter("max_horizon") {
+        let (months, fixed) = params::parse_calendar_interval(&horizon.to_string())?;
+        let months = match u32::try_from(months) {
+            Ok(months)
+                if fixed >= TimeDelta::zero() && (months > 0 || fixed > TimeDelta::zero()) =>
+            {
+                months
+            }
+            _ => return Err("max_horizon must be a positive interval".into()),
+        };
+        if let Some(end) = horizon_end((*data).start, months, fixed) {
+            (*data).until = (*data).until.min(end);
+        }
+        if let Some(windows) = windows.as_mut() {
+            for (start, until) in windows.iter_mut() {
+                if let Some(end) = horizon_end(*start, months, fixed) {
+                    *until = (*until).min(end);
+                }
+            }
+        }
+    }
+    if let Some(windows) = windows {
+        (*data).windows = Box::into_raw(Box::new(windows));
+    }
+
+    // Daylight saving transitions can shift a fixed interval in local
+    // time, so the arithmetic path is only taken when iterating in UTC.
+    (*data).fixed_interval = if zone.generation_zone() == Tz::UTC && sources.len() == 1 {
+        dense::fixed_interval_seconds(&sources[0])
+    } else {
+        None
+    };
+
+    (*data).filters = OccurrenceFilters::from_bind(bind)?;
+
+    (*data).point_query = match bind.get_named_parameter("point_query") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    (*data).open_interval = match bind.get_named_parameter("open_interval") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    (*data).prime = match bind.get_named_parameter("prime") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    (*data).shift = match bind.get_named_parameter("shift") {
+        Some(value) => Some(Shift {
+            amount: params::parse_interval(&value.to_string())?,
+            mode: match bind.get_named_parameter("shift_mode") {
+                Some(mode) => ShiftMode::parse(&mode.to_string())?,
+                None => ShiftMode::WallClock,
+            },
+        }),
+        None => None,
+    };
+    if (*data).shift.is_none() && bind.get_named_parameter("shift_mode").is_some() {
+        return Err("shift_mode requires a shift".into());
+    }
+
+    (*data).emit_empty_row = match bind.get_named_parameter("emit_empty_row") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    (*data).require_nonempty = match bind.get_named_parameter("require_nonempty") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    // Only the output is affected, patterns always number weekdays from 0
+    // for Sunday.
+    (*data).unix_weekdays = match bind.get_named_parameter("dow_numbering") {
+        Some(value) => match value.to_string().as_str() {
+            "iso" => false,
+            "unix" => true,
+            other => {
+                return Err(format!(
+                    "Invalid dow_numbering '{}', expected 'iso' or 'unix'",
+                    other
+                )
+                .into())
+            }
+        },
+        None => false,
+    };
+    if (*data).calendar_parts_column.is_none()
+        && bind.get_named_parameter("dow_numbering").is_some()
+    {
+        return Err("dow_numbering requires with_calendar_parts".into());
+    }
+
+    (*data).validate_only = match bind.get_named_parameter("validate_only") {
+        Some(value) => value.to_string() == "true",
+        None => false,
+    };
+
+    (*data).yield_interval = match bind.get_named_parameter("yield_interval_us") {
+        Some(value) => match u64::try_from(value.to_int64()) {
+            Ok(micros) if micros > 0 => Some(Duration::from_micros(micros)),
+            _ => return Err("yield_interval_us must be positive".into()),
+        },
+        None => None,
+    };
+
+    // Occurrences are instants and do not depend on where a week starts, the
+    // value is only validated so it can be passed uniformly to every function.
+    if let Some(value) = bind.get_named_parameter("week_starts_on") {
+        weekly::week_start_offset(&value.to_string())?;
+    }
+
+    Ok(())
+}
+
+// The `cron_from(pattern, start)` overload, accepting the start time
+// positionally. DuckDB doesn't allow a second table function named `cron`.
+struct CronFromVTab;
+
+impl VTab for CronFromVTab {
+    type InitData = CronInitData;
+    type BindData = CronBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut CronBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        CronVTab::bind(bind, data)
+    }
+
+    unsafe fn init(
+        init: &InitInfo,
+        data: *mut CronInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        CronVTab::init(init, data)
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        CronVTab::func(func, output)
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        CronVTab::named_parameters()
+    }
+}
+
+// A stable entry point over `cron()` for the common case of generating the
+// occurrences between two bounds, so queries using it keep working if the
+// table function's signature evolves.
+const CRON_RANGE_MACRO: &str =
+    "CREATE OR REPLACE MACRO cron_range(pattern, start_at, until_at) AS TABLE \
+     SELECT cron FROM cron(pattern, start := start_at, until := until_at)";
+
 // Exposes a extern C function named "libcrontab_init" in the compiled dynamic library,
 // the "entrypoint" that duckdb will use to load the extension.
 #[duckdb_entrypoint]
 pub fn libcrontab_init(conn: Connection) -> Result<(), Box<dyn Error>> {
     conn.register_table_function::<CronVTab>("cron")?;
+    conn.register_table_function::<next_change::NextChangeVTab>("cron_next_change")?;
+    conn.register_table_function::<seconds::WithSecondsVTab>("cron_with_seconds")?;
+    conn.register_table_function::<overlap::OverlapVTab>("cron_overlap_minutes")?;
+    conn.register_table_function::<describe_next::DescribeNextVTab>("cron_describe_next")?;
+    conn.register_table_function::<weekly::WeeklyVTab>("cron_weekly_pattern")?;
+    conn.register_table_function::<missed::MissedVTab>("cron_missed")?;
+    conn.register_table_function::<business_day::BusinessDayVTab>("cron_next_business_day")?;
+    conn.register_table_function::<density::DensityVTab>("cron_density")?;
+    conn.register_table_function::<at_index::AtIndexVTab>("cron_at_index")?;
+    conn.register_table_function::<subset::SubsetVTab>("cron_is_subset")?;
+    conn.register_table_function::<timeline::TimelineVTab>("cron_timeline")?;
+    conn.register_table_function::<normalize::NormalizeVTab>("cron_normalize")?;
+    conn.register_table_function::<normalize::RoundTripVTab>("cron_round_trip_test")?;
+    conn.register_table_function::<phase::PhaseVTab>("cron_phase")?;
+    conn.register_table_function::<equivalent::EquivalentVTab>("cron_equivalent")?;
+    conn.register_table_function::<gaps::GapsVTab>("cron_gaps")?;
+    conn.register_table_function::<fires_between::FiresBetweenVTab>("cron_fires_between")?;
+    conn.register_table_function::<fires_between::FiresBetweenVTab>("cron_clamp")?;
+    conn.register_table_function::<age::AgeVTab>("cron_age")?;
+    conn.register_table_function::<after::AfterVTab>("cron_after")?;
+    conn.register_table_function::<systemd::OnCalendarVTab>("cron_to_systemd_oncalendar")?;
+    conn.register_table_function::<weekday_ordinal::WeekdayOrdinalVTab>("cron_weekday_ordinal")?;
+    conn.register_table_function::<schedule_hash::ScheduleHashVTab>("cron_schedule_hash")?;
+    conn.register_table_function::<crontab::CrontabVTab>("cron_crontab")?;
+    conn.register_table_function::<overlapping_windows::OverlappingWindowsVTab>(
+        "cron_overlapping_windows",
+    )?;
+    conn.register_table_function::<preview::PreviewVTab>("cron_preview")?;
+    conn.register_table_function::<validate_batch::ValidateBatchVTab>("cron_validate_batch")?;
+    conn.register_table_function::<next_matching::NextMatchingVTab>("cron_next_matching")?;
+    conn.register_table_function::<describe_delta::DescribeDeltaVTab>("cron_describe_delta")?;
+    conn.register_table_function::<normalize::NormalizeBatchVTab>("cron_normalize_batch")?;
+    conn.register_table_function::<fire_indices::FireIndicesVTab>("cron_fire_indices")?;
+    conn.register_table_function::<parse::ParseVTab>("cron_parse")?;
+    conn.register_table_function::<matches_local::MatchesLocalVTab>("cron_matches_local")?;
+    conn.register_table_function::<next_across_zones::NextAcrossZonesVTab>(
+        "cron_next_across_zones",
+    )?;
+    conn.register_table_function::<heat::HeatVTab>("cron_heat")?;
+    conn.register_table_function::<bitmap::BitmapVTab>("cron_to_bitmap")?;
+    conn.register_table_function::<since::SinceVTab>("cron_since")?;
+    conn.register_table_function::<align_series::AlignSeriesVTab>("cron_align_series")?;
+    conn.register_table_function::<first_after_gap::FirstAfterGapVTab>("cron_first_after_gap")?;
+    conn.register_table_function::<merge_schedules::MergeSchedulesVTab>("cron_merge_schedules")?;
+    conn.register_table_function::<summary::SummaryVTab>("cron_describe_schedule_table")?;
+    conn.register_table_function::<spread::SpreadVTab>("cron_next_n_across_window")?;
+    conn.register_table_function::<tz_version::TzVersionVTab>("cron_tz_version")?;
+    conn.register_table_function::<conflicts::ConflictsVTab>("cron_conflicts_batch")?;
+    conn.register_table_function::<eventbridge::EventBridgeVTab>("cron_to_aws_eventbridge")?;
+    conn.register_table_function::<membership::WindowMembershipVTab>("cron_window_membership")?;
+    conn.register_table_function::<at_index::AtIndexVTab>("cron_skip_n")?;
+    conn.register_table_function::<infer::PatternFromTimesVTab>("cron_pattern_from_times")?;
+    conn.register_table_function::<period::InPeriodVTab>("cron_in_period")?;
+    conn.register_table_function::<crontab_line::CrontabLineVTab>("cron_to_crontab_line")?;
+    conn.register_table_function::<CronFromVTab>("cron_from")?;
+    conn.execute_batch(CRON_RANGE_MACRO)?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zones_default_to_utc() {
+        assert_eq!(resolve_zone(None, false, None), Ok(Zone::Named(Tz::UTC)));
+        assert_eq!(resolve_zone(None, true, None), Ok(Zone::Named(Tz::UTC)));
+        assert_eq!(
+            resolve_zone(Some("Asia/Tokyo"), false, None),
+            Ok(Zone::Named(Tz::Asia__Tokyo))
+        );
+    }
+
+    #[test]
+    fn utc_cannot_be_combined_with_a_timezone() {
+        assert!(resolve_zone(Some("UTC"), true, None).is_err());
+        assert!(resolve_zone(Some("Europe/Paris"), true, None).is_err());
+    }
+
+    #[test]
+    fn fixed_offsets_generate_in_utc() {
+        let zone = resolve_zone(None, false, Some("05:30:00")).unwrap();
+        assert_eq!(zone, Zone::Fixed(FixedOffset::east_opt(19800).unwrap()));
+        assert_eq!(zone.generation_zone(), Tz::UTC);
+        assert_eq!(zone.shift(), TimeDelta::minutes(330));
+
+        let zone = resolve_zone(None, false, Some("-08:00:00")).unwrap();
+        assert_eq!(zone.shift(), TimeDelta::hours(-8));
+    }
+
+    #[test]
+    fn fixed_offsets_are_checked() {
+        assert!(resolve_zone(None, false, Some("1 day")).is_err());
+        assert!(resolve_zone(None, false, Some("00:00:00.5")).is_err());
+        assert!(resolve_zone(Some("UTC"), false, Some("01:00:00")).is_err());
+        assert!(resolve_zone(None, true, Some("01:00:00")).is_err());
+    }
+
+    fn at(hour: u32) -> DateTime<Tz> {
+        Tz::UTC.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn windows_exclude_until() {
+        let until = at(12);
+        assert_eq!(
+            should_emit(at(11), true, until, None, 0, 0, 10),
+            Decision::Emit
+        );
+        assert_eq!(
+            should_emit(at(11), false, until, None, 0, 0, 10),
+            Decision::Skip
+        );
+        assert_eq!(
+            should_emit(at(12), true, until, None, 0, 0, 10),
+            Decision::Stop
+        );
+        assert_eq!(
+            should_emit(at(13), false, until, None, 0, 0, 10),
+            Decision::Stop
+        );
+    }
+
+    #[test]
+    fn chunks_stop_when_full() {
+        let until = at(12);
+        assert_eq!(
+            should_emit(at(11), true, until, None, 0, 9, 10),
+            Decision::Emit
+        );
+        assert_eq!(
+            should_emit(at(11), true, until, None, 0, 10, 10),
+            Decision::Stop
+        );
+        assert_eq!(
+            should_emit(at(11), false, until, None, 0, 10, 10),
+            Decision::Stop
+        );
+        // Earlier chunks don't count towards this one.
+        assert_eq!(
+            should_emit(at(11), true, until, None, 50, 0, 10),
+            Decision::Emit
+        );
+    }
+
+    #[test]
+    fn collapsed_windows_return_their_first_occurrence() {
+        let (until, limit) = (at(12), Some(at(15)));
+        assert_eq!(
+            should_emit(at(12), true, until, limit, 0, 0, 10),
+            Decision::Emit
+        );
+        assert_eq!(
+            should_emit(at(14), true, until, limit, 0, 0, 10),
+            Decision::Emit
+        );
+        assert_eq!(
+            should_emit(at(15), true, until, limit, 0, 0, 10),
+            Decision::Emit
+        );
+        // Filtered occurrences are passed over until one passes.
+        assert_eq!(
+            should_emit(at(13), false, until, limit, 0, 0, 10),
+            Decision::Skip
+        );
+        // Only one occurrence, searched for up to the limit.
+        assert_eq!(
+            should_emit(at(16), true, until, limit, 0, 0, 10),
+            Decision::Stop
+        );
+        assert_eq!(
+            should_emit(at(13), true, until, limit, 0, 1, 10),
+            Decision::Stop
+        );
+        assert_eq!(
+            should_emit(at(13), true, until, limit, 1, 0, 10),
+            Decision::Stop
+        );
+    }
+
+    // Every minute of June 2024, counting how many were generated.
+    fn minutes(
+        generated: &std::cell::Cell<usize>,
+    ) -> impl Iterator<Item = (usize, DateTime<Tz>)> + '_ {
+        let start = Tz::UTC.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        (0..).map(move |i| {
+            generated.set(generated.get() + 1);
+            (0, start + TimeDelta::minutes(i))
+        })
+    }
+
+    #[test]
+    fn chunks_generate_lazily() {
+        let generated = std::cell::Cell::new(0);
+        let until = Tz::UTC.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+        let (rows, yielded) =
+            collect_chunk(minutes(&generated), |_| true, until, None, 0, 2048, None);
+        assert_eq!(rows.len(), 2048);
+        assert!(!yielded);
+        // The month has 43200 minutes, only one past the chunk is looked at.
+        assert_eq!(generated.get(), 2049);
+    }
+
+    #[test]
+    fn chunks_end_at_until() {
+        let generated = std::cell::Cell::new(0);
+        let until = Tz::UTC.with_ymd_and_hms(2024, 6, 1, 0, 10, 0).unwrap();
+        let (rows, _) = collect_chunk(minutes(&generated), |_| true, until, None, 0, 2048, None);
+        assert_eq!(rows.len(), 10);
+        assert_eq!(
+            rows.last().map(|&(_, x)| x),
+            Some(until - TimeDelta::minutes(1))
+        );
+        assert_eq!(generated.get(), 11);
+    }
+
+    #[test]
+    fn count_and_until_compose() {
+        let generated = std::cell::Cell::new(0);
+        let until = Tz::UTC.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap();
+        let (mut rows, _) =
+            collect_chunk(minutes(&generated), |_| true, until, None, 0, 2048, None);
+        assert!(truncate_to_count(&mut rows, 10, 0));
+        assert_eq!(rows.len(), 10);
+
+        // The count is reached partway through a later chunk.
+        let (mut rows, _) =
+            collect_chunk(minutes(&generated), |_| true, until, None, 2048, 2048, None);
+        assert!(truncate_to_count(&mut rows, 2058, 2048));
+        assert_eq!(rows.len(), 10);
+
+        // A count beyond the window leaves the end to `until`.
+        let mut rows: Vec<Row> = vec![(Some(0), until - TimeDelta::minutes(1))];
+        assert!(!truncate_to_count(&mut rows, 10, 5));
+        assert_eq!(rows.len(), 1);
+    }
+
+    // Scans the window of `start` and `until` chunk by chunk the way
+    // `CronVTab::func` does, returning the chunks.
+    fn scan(pattern: &str, start: DateTime<Tz>, until: DateTime<Tz>, prime: bool) -> Vec<Vec<Row>> {
+        let cron = schedule::parse_pattern(pattern).unwrap();
+        let mut init = dirty_init();
+        init.reset();
+        scan_with(&mut init, &cron, start, until, prime)
+    }
+
+    // Continues a scan with the state in `init`.
+    fn scan_with(
+        init: &mut CronInitData,
+        cron: &Cron,
+        start: DateTime<Tz>,
+        until: DateTime<Tz>,
+        prime: bool,
+    ) -> Vec<Vec<Row>> {
+        let mut chunks = Vec::new();
+        while !init.done {
+            let primed = prime && init.cursor.is_none() && init.emitted == 0;
+            let (rows, done) = range_chunk(
+                |requested| {
+                    let occurrences: Box<dyn Iterator<Item = DateTime<Tz>>> = match init.cursor {
+                        Some(cursor) => Box::new(cron.iter_after(cursor)),
+                        None => Box::new(cron.iter_from(start)),
+                    };
+                    let chunk = collect_chunk(
+                        occurrences.map(|x| (0, x)),
+                        |_| true,
+                        until,
+                        None,
+                        init.emitted,
+                        requested,
+                        None,
+                    );
+                    if let Some(&(_, last)) = chunk.0.last() {
+                        init.cursor = Some(last);
+                    }
+                    init.emitted += chunk.0.len();
+                    chunk
+                },
+                start,
+                primed,
+                2048,
+            );
+            init.done = done;
+            chunks.push(rows);
+        }
+        chunks
+    }
+
+    // The state left behind by an earlier scan.
+    fn dirty_init() -> CronInitData {
+        CronInitData {
+            done: true,
+            cursor: Some(Tz::UTC.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap()),
+            cursor_pattern: 1,
+            emitted: 5000,
+            window: 2,
+            window_exhausted: true,
+            written: 5000,
+            pending: Some((
+                Some(0),
+                Tz::UTC.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap(),
+            )),
+            yielded: true,
+        }
+    }
+
+    #[test]
+    fn rescanning_the_same_bind_gives_the_same_rows() {
+        let cron = schedule::parse_pattern("*/7 * * * *").unwrap();
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let until = start + TimeDelta::days(30);
+
+        let mut init = dirty_init();
+        init.reset();
+        let first = scan_with(&mut init, &cron, start, until, true);
+        assert!(first.len() > 2);
+        // Without a reset the finished scan produces nothing more.
+        assert!(scan_with(&mut init, &cron, start, until, true).is_empty());
+        init.reset();
+        assert_eq!(scan_with(&mut init, &cron, start, until, true), first);
+    }
+
+    #[test]
+    fn exact_multiples_of_a_vector_end_with_an_empty_chunk() {
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let until = start + TimeDelta::minutes(3 * 2048);
+        let chunks = scan("* * * * *", start, until, false);
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2048, 2048, 2048, 0]);
+
+        let rows: Vec<DateTime<Tz>> = chunks.concat().iter().map(|&(_, x)| x).collect();
+        assert_eq!(rows.first(), Some(&start));
+        assert_eq!(rows.last(), Some(&(until - TimeDelta::minutes(1))));
+        assert!(rows
+            .windows(2)
+            .all(|x| x[1] - x[0] == TimeDelta::minutes(1)));
+    }
+
+    #[test]
+    fn partial_chunks_end_the_scan() {
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let until = start + TimeDelta::minutes(2 * 2048 + 5);
+        let sizes: Vec<usize> = scan("* * * * *", start, until, false)
+            .iter()
+            .map(Vec::len)
+            .collect();
+        assert_eq!(sizes, vec![2048, 2048, 5]);
+    }
+
+    #[test]
+    fn primed_rows_take_a_place_in_the_first_chunk() {
+        // Starting between occurrences, the primed start is an extra row.
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap();
+        let until = start + TimeDelta::minutes(2 * 2048);
+        let chunks = scan("* * * * *", start, until, true);
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2048, 2048, 1]);
+        assert_eq!(chunks[0][0], (None, start));
+
+        // Starting on an occurrence, it is only returned once.
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let until = start + TimeDelta::minutes(2 * 2048);
+        let chunks = scan("* * * * *", start, until, true);
+        let sizes: Vec<usize> = chunks.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![2047, 2048, 1]);
+        assert_eq!(chunks[0][0], (Some(0), start));
+    }
+
+    #[test]
+    fn yielded_chunks_dont_end_the_scan() {
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let (rows, done) = range_chunk(|_| (vec![(Some(0), start)], true), start, false, 2048);
+        assert_eq!(rows.len(), 1);
+        assert!(!done);
+        let (_, done) = range_chunk(|_| (vec![(Some(0), start)], false), start, false, 2048);
+        assert!(done);
+    }
+
+    #[test]
+    fn start_is_given_once() {
+        assert_eq!(start_parameter(None, None), Ok(None));
+        assert_eq!(start_parameter(Some(1), None), Ok(Some(1)));
+        assert_eq!(start_parameter(None, Some(2)), Ok(Some(2)));
+        assert!(start_parameter(Some(1), Some(2)).is_err());
+    }
+
+    #[test]
+    fn strict_seconds_zero_allows_only_second_zero() {
+        assert!(check_seconds_zero("0 * * * * *").is_ok());
+        assert!(check_seconds_zero("* * * * *").is_ok());
+        assert!(check_seconds_zero("@hourly").is_ok());
+        let err = check_seconds_zero("30 * * * * *").unwrap_err();
+        assert!(err.contains("second '30'"), "{}", err);
+        assert!(check_seconds_zero("* * * * * *").is_err());
+        assert!(check_seconds_zero("0,30 * * * * *").is_err());
+    }
+
+    // The rows of a scan of New York midnights, as UTC text.
+    fn new_york_midnights(start: &str, until: &str) -> Vec<String> {
+        let zone = Zone::Named(chrono_tz::America::New_York);
+        let micros = |text: &str| params::parse_timestamp_micros(text).unwrap();
+        let start = zone.bound_from_micros(micros(start)).unwrap();
+        let until = zone.bound_from_micros(micros(until)).unwrap();
+        scan("0 0 * * *", start, until, false)
+            .concat()
+            .iter()
+            .map(|&(_, x)| x.with_timezone(&Tz::UTC).format("%m-%d %H:%M").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn until_is_an_instant_across_spring_forward() {
+        // Midnight of March 11th is 04:00 UTC, after the clocks sprang forward.
+        assert_eq!(
+            new_york_midnights("2024-03-09 05:00:00", "2024-03-11 04:00:00"),
+            vec!["03-09 05:00", "03-10 05:00"]
+        );
+        assert_eq!(
+            new_york_midnights("2024-03-09 05:00:00", "2024-03-11 04:00:01"),
+            vec!["03-09 05:00", "03-10 05:00", "03-11 04:00"]
+        );
+        // It is not mistaken for 05:00 UTC, midnight in EST.
+        assert_eq!(
+            new_york_midnights("2024-03-09 05:00:00", "2024-03-11 04:59:59"),
+            vec!["03-09 05:00", "03-10 05:00", "03-11 04:00"]
+        );
+    }
+
+    #[test]
+    fn until_is_an_instant_across_fall_back() {
+        // Midnight of November 4th is 05:00 UTC, after the clocks fell back.
+        assert_eq!(
+            new_york_midnights("2024-11-02 04:00:00", "2024-11-04 05:00:00"),
+            vec!["11-02 04:00", "11-03 04:00"]
+        );
+        // Nor for 04:00 UTC, midnight in EDT.
+        assert_eq!(
+            new_york_midnights("2024-11-02 04:00:00", "2024-11-04 04:00:00"),
+            vec!["11-02 04:00", "11-03 04:00"]
+        );
+        assert_eq!(
+            new_york_midnights("2024-11-02 04:00:00", "2024-11-04 05:00:01"),
+            vec!["11-02 04:00", "11-03 04:00", "11-04 05:00"]
+        );
+    }
+
+    #[test]
+    fn collapsed_windows_settled_without_generating() {
+        let patterns: Vec<Cron> = ["0 * * * *", "*/30 * * * *", "15 * * * *"]
+            .iter()
+            .map(|x| schedule::parse_pattern(x).unwrap())
+            .collect();
+        let (noon, half_past) = (at(12), at(12) + TimeDelta::minutes(30));
+        let rows = |x, open_interval, point_query, every_source| {
+            collapsed_rows(&patterns, x, x, open_interval, point_query, every_source).unwrap()
+        };
+
+        // Open intervals are empty, even when a pattern fires at the instant.
+        assert_eq!(rows(noon, true, false, false), Some(vec![]));
+        assert_eq!(rows(noon, true, true, true), Some(vec![]));
+
+        // Point queries return the instant once, or per matching pattern.
+        assert_eq!(rows(noon, false, true, false), Some(vec![(Some(0), noon)]));
+        assert_eq!(
+            rows(noon, false, true, true),
+            Some(vec![(Some(0), noon), (Some(1), noon)])
+        );
+        assert_eq!(
+            rows(half_past, false, true, false),
+            Some(vec![(Some(1), half_past)])
+        );
+        let quarter_to = at(12) + TimeDelta::minutes(45);
+        assert_eq!(rows(quarter_to, false, true, true), Some(vec![]));
+
+        // Otherwise the window is generated as usual.
+        assert_eq!(rows(noon, false, false, false), None);
+        assert_eq!(
+            collapsed_rows(&patterns, noon, at(13), true, true, false).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn numbers_weekdays_iso_or_unix() {
+        let friday = Tz::UTC.with_ymd_and_hms(2024, 6, 14, 12, 0, 0).unwrap();
+        assert_eq!(weekday_number(friday.weekday(), false), 5);
+        assert_eq!(weekday_number(friday.weekday(), true), 5);
+
+        // The numberings only differ on Sunday.
+        let sunday = Tz::UTC.with_ymd_and_hms(2024, 6, 16, 12, 0, 0).unwrap();
+        assert_eq!(weekday_number(sunday.weekday(), false), 7);
+        assert_eq!(weekday_number(sunday.weekday(), true), 0);
+        let monday = Tz::UTC.with_ymd_and_hms(2024, 6, 17, 12, 0, 0).unwrap();
+        assert_eq!(weekday_number(monday.weekday(), false), 1);
+        assert_eq!(weekday_number(monday.weekday(), true), 1);
+    }
+}
@@ -0,0 +1,123 @@
+// `cron_matches_local(pattern, ts, zone)` checks a naive timestamp holding a
+// wall-clock time in some zone against a pattern, for event tables that store
+// local times without their zone.
+
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{parse_pattern_or_error, parse_timezone, pattern_parameter};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct MatchesLocalBindData {
+    matches: bool,
+}
+
+impl Free for MatchesLocalBindData {}
+
+// Whether the pattern fires at the wall-clock time `micros`, given like a
+// DuckDB TIMESTAMP, in `timezone`.
+//
+// A time repeated when clocks fall back is taken at its first occurrence,
+// which has the same wall-clock fields. A time skipped when clocks spring
+// forward never happens there, so nothing fires at it.
+pub(crate) fn matches_local(cron: &Cron, micros: i64, timezone: &Tz) -> Result<bool, String> {
+    let naive = DateTime::from_timestamp(micros.div_euclid(1_000_000), 0)
+        .ok_or("Invalid timestamp")?
+        .naive_utc();
+    match timezone.from_local_datetime(&naive).earliest() {
+        Some(local) => cron.is_time_matching(&local).map_err(|err| err.to_string()),
+        None => Ok(false),
+    }
+}
+
+pub(crate) struct MatchesLocalVTab;
+
+impl VTab for MatchesLocalVTab {
+    type InitData = OnceInitData;
+    type BindData = MatchesLocalBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut MatchesLocalBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("matches", LogicalType::new(LogicalTypeId::Boolean));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = parse_timezone(Some(&bind.get_parameter(2).to_string()))?;
+        (*data).matches =
+            matches_local(&cron, bind.get_parameter(1).to_int64_timestamp(), &timezone)?;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<MatchesLocalBindData>();
+        emit_once(init_info, output, Some((*bind_info).matches));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::parse_pattern;
+    use chrono::NaiveDate;
+    use chrono_tz::America::New_York;
+
+    // Whether a pattern fires at a New York wall-clock time in 2024.
+    fn fires(pattern: &str, (month, day): (u32, u32), hour: u32, minute: u32) -> bool {
+        let micros = NaiveDate::from_ymd_opt(2024, month, day)
+            .and_then(|x| x.and_hms_opt(hour, minute, 0))
+            .unwrap()
+            .and_utc()
+            .timestamp()
+            * 1_000_000;
+        matches_local(&parse_pattern(pattern).unwrap(), micros, &New_York).unwrap()
+    }
+
+    #[test]
+    fn matches_wall_clock_times() {
+        assert!(fires("30 2 * * *", (3, 11), 2, 30));
+        assert!(fires("0 9 * * 1-5", (6, 14), 9, 0));
+        assert!(!fires("0 9 * * 1-5", (6, 15), 9, 0));
+    }
+
+    #[test]
+    fn skipped_times_never_match() {
+        // Clocks jumped from 02:00 to 03:00 on March 10th.
+        assert!(!fires("30 2 * * *", (3, 10), 2, 30));
+        assert!(!fires("* * * * *", (3, 10), 2, 0));
+        assert!(fires("0 3 * * *", (3, 10), 3, 0));
+    }
+
+    #[test]
+    fn repeated_times_match() {
+        // 01:30 happened twice on November 3rd, the first is taken.
+        assert!(fires("30 1 * * *", (11, 3), 1, 30));
+        assert!(fires("30 1 * * 0", (11, 3), 1, 30));
+    }
+}
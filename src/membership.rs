@@ -0,0 +1,91 @@
+// `cron_window_membership(pattern, duration, ts)` checks whether an instant
+// falls within the window, lasting `duration`, opened by some occurrence,
+// e.g. whether a maintenance window is open.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, floor_occurrence, parse_pattern_or_error, pattern_parameter,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct WindowMembershipBindData {
+    active: bool,
+}
+
+impl Free for WindowMembershipBindData {}
+
+// Whether `at` lies within `[fire, fire + duration)` of some occurrence.
+//
+// Every window lasts the same, so the one opened by the last occurrence at or
+// before `at` ends last, and overlapping earlier windows can only cover `at`
+// if it does.
+pub(crate) fn within_window(cron: &Cron, duration: TimeDelta, at: DateTime<Tz>) -> bool {
+    floor_occurrence(cron, at).is_some_and(|fire| at < fire + duration)
+}
+
+pub(crate) struct WindowMembershipVTab;
+
+impl VTab for WindowMembershipVTab {
+    type InitData = OnceInitData;
+    type BindData = WindowMembershipBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut WindowMembershipBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("active", LogicalType::new(LogicalTypeId::Boolean));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let duration = parse_interval(&bind.get_parameter(1).to_string())?;
+        if duration <= TimeDelta::zero() {
+            return Err("The duration must be positive".into());
+        }
+        let timezone = timezone_parameter(bind)?;
+        let at = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid timestamp")?;
+        (*data).active = within_window(&cron, duration, at);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<WindowMembershipBindData>();
+        emit_once(init_info, output, Some((*bind_info).active));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Interval),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,232 @@
+// `cron_merge_schedules(schedules, start, until)` sweeps the runs of several
+// named schedules, each lasting its own duration, into a coverage timeline of
+// segments labeled with the schedules active in them.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::params::{parse_interval, parse_list, parse_struct};
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern, search_limit, timezone_parameter,
+};
+
+// A stretch of time during which the same schedules are running, given by
+// their indexes.
+type Segment = (DateTime<Tz>, DateTime<Tz>, Vec<usize>);
+
+#[repr(C)]
+pub(crate) struct MergeSchedulesBindData {
+    names: *mut Vec<String>,
+    segments: *mut Vec<Segment>,
+}
+
+impl Free for MergeSchedulesBindData {
+    fn free(&mut self) {
+        unsafe {
+            if !self.names.is_null() {
+                drop(Box::from_raw(self.names));
+            }
+            if !self.segments.is_null() {
+                drop(Box::from_raw(self.segments));
+            }
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct MergeSchedulesInitData {
+    // The index of the next segment to emit.
+    cursor: usize,
+}
+
+impl Free for MergeSchedulesInitData {}
+
+// Parses DuckDB's rendering of a LIST(STRUCT(name VARCHAR, pattern VARCHAR,
+// duration INTERVAL)) into the names, patterns and run durations of the
+// schedules.
+fn parse_schedules(text: &str) -> Result<Vec<(String, Cron, TimeDelta)>, String> {
+    let mut schedules = Vec::new();
+    for schedule in parse_list(text)? {
+        let (mut name, mut pattern, mut duration) = (None, None, None);
+        for (field, value) in parse_struct(&schedule)? {
+            match field.as_str() {
+                "name" => name = Some(value),
+                "pattern" => pattern = Some(value),
+                "duration" => duration = Some(parse_interval(&value)?),
+                _ => return Err(format!("Unknown schedule field: {}", field)),
+            }
+        }
+        let name = name.ok_or("Every schedule needs a name")?;
+        let pattern = pattern.ok_or_else(|| format!("Schedule '{}' has no pattern", name))?;
+        let duration = duration.ok_or_else(|| format!("Schedule '{}' has no duration", name))?;
+        if duration <= TimeDelta::zero() {
+            return Err(format!(
+                "The duration of schedule '{}' must be positive",
+                name
+            ));
+        }
+        let cron = parse_pattern(&pattern).map_err(|err| {
+            format!(
+                "Failed to parse cron expression of schedule '{}': {}",
+                name, err
+            )
+        })?;
+        schedules.push((name, cron, duration));
+    }
+    Ok(schedules)
+}
+
+// Sweeps the runs starting within `[start, until)` into segments, starting a
+// new one whenever the set of running schedules changes. Runs of the same
+// schedule may overlap each other, and idle stretches produce no segment.
+pub(crate) fn sweep(
+    schedules: &[(String, Cron, TimeDelta)],
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+) -> Vec<Segment> {
+    // Each run adds one to the count of its schedule at its start and
+    // removes it at its end.
+    let mut events: Vec<(DateTime<Tz>, usize, i64)> = Vec::new();
+    for (i, (_, cron, duration)) in schedules.iter().enumerate() {
+        for x in occurrences_between(cron, start, until) {
+            events.push((x, i, 1));
+            events.push((x + *duration, i, -1));
+        }
+    }
+    events.sort_by_key(|&(at, _, _)| at);
+
+    let mut running = vec![0i64; schedules.len()];
+    let active = |running: &[i64]| -> Vec<usize> {
+        (0..running.len()).filter(|&i| running[i] > 0).collect()
+    };
+    let mut segments = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    let mut since = start;
+    let mut events = events.into_iter().peekable();
+    while let Some((at, i, delta)) = events.next() {
+        running[i] += delta;
+        // Events at the same instant are applied together.
+        if events.peek().is_some_and(|&(next, _, _)| next == at) {
+            continue;
+        }
+        let next = active(&running);
+        if next != current {
+            if !current.is_empty() {
+                segments.push((since, at, current));
+            }
+            current = next;
+            since = at;
+        }
+    }
+    segments
+}
+
+pub(crate) struct MergeSchedulesVTab;
+
+impl VTab for MergeSchedulesVTab {
+    type InitData = MergeSchedulesInitData;
+    type BindData = MergeSchedulesBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut MergeSchedulesBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).names = null_mut();
+        (*data).segments = null_mut();
+        bind.add_result_column(
+            "interval_start",
+            LogicalType::new(LogicalTypeId::TimestampS),
+        );
+        bind.add_result_column("interval_end", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column(
+            "active_schedules",
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Varchar)),
+        );
+
+        let schedules = parse_schedules(&bind.get_parameter(0).to_string())?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        // Every run is held in memory while sweeping.
+        if until > search_limit(start) {
+            return Err("The range of cron_merge_schedules may span at most five years".into());
+        }
+
+        let segments = sweep(&schedules, start, until);
+        let names: Vec<String> = schedules.into_iter().map(|(name, _, _)| name).collect();
+        (*data).names = Box::into_raw(Box::new(names));
+        (*data).segments = Box::into_raw(Box::new(segments));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut MergeSchedulesInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).cursor = 0;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<MergeSchedulesInitData>();
+        let bind_info = func.get_bind_data::<MergeSchedulesBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let names = &*(*bind_info).names;
+        let segments = &*(*bind_info).segments;
+        let first = (*init_info).cursor.min(segments.len());
+        let chunk = &segments[first..(first + max_items).min(segments.len())];
+
+        let starts: Vec<i64> = chunk.iter().map(|(x, _, _)| x.timestamp()).collect();
+        let ends: Vec<i64> = chunk.iter().map(|(_, x, _)| x.timestamp()).collect();
+        output.flat_vector(0).copy(&starts);
+        output.flat_vector(1).copy(&ends);
+
+        let mut active = output.list_vector(2);
+        let total: usize = chunk.iter().map(|(_, _, active)| active.len()).sum();
+        let mut labels = active.child(total);
+        let mut offset = 0;
+        for (row, (_, _, running)) in chunk.iter().enumerate() {
+            for (k, &i) in running.iter().enumerate() {
+                labels.insert(offset + k, names[i].as_str());
+            }
+            active.set_entry(row, offset, running.len());
+            offset += running.len();
+        }
+        active.set_len(total);
+
+        output.set_len(chunk.len());
+        (*init_info).cursor = first + chunk.len();
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::list(&LogicalType::struct_type(&[
+                ("name", LogicalType::new(LogicalTypeId::Varchar)),
+                ("pattern", LogicalType::new(LogicalTypeId::Varchar)),
+                ("duration", LogicalType::new(LogicalTypeId::Interval)),
+            ])),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
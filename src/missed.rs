@@ -0,0 +1,91 @@
+// `cron_missed(pattern, last_run, now)` emits the runs a scheduler missed
+// between its last run and now, i.e. the set it would have to catch up on.
+
+use chrono::TimeDelta;
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, timezone_parameter,
+};
+use crate::stream::{emit_occurrences, OccurrenceBindData, OccurrenceInitData};
+
+pub(crate) struct MissedVTab;
+
+impl VTab for MissedVTab {
+    type InitData = OccurrenceInitData;
+    type BindData = OccurrenceBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut OccurrenceBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).init();
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let last_run = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid last run time")?;
+        let now = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid now time")?;
+
+        // Occurrences fall on whole seconds, so the first one after the last
+        // run is at least a second later.
+        (*data).start = last_run + TimeDelta::seconds(1);
+        (*data).until = now;
+
+        let coalesce = match bind.get_named_parameter("coalesce") {
+            Some(value) => value.to_string() == "true",
+            None => false,
+        };
+        if coalesce {
+            // Dropping the missed runs and running once is represented by
+            // the most recent missed run alone.
+            match cron.iter_after(last_run).take_while(|&x| x <= now).last() {
+                Some(latest) => (*data).start = latest,
+                None => (*data).start = now + TimeDelta::seconds(1),
+            }
+        }
+
+        (*data).set_pattern(cron);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OccurrenceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).init();
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OccurrenceInitData>();
+        let bind_info = func.get_bind_data::<OccurrenceBindData>();
+        emit_occurrences(bind_info, init_info, output)
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![
+            (
+                "coalesce".to_string(),
+                LogicalType::new(LogicalTypeId::Boolean),
+            ),
+            (
+                "timezone".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+        ])
+    }
+}
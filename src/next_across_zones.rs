@@ -0,0 +1,129 @@
+// `cron_next_across_zones(pattern, from, zones)` finds the next time a
+// local-time schedule fires in any of several zones, for follow-the-sun
+// systems asking who runs next globally.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use std::ptr::null_mut;
+
+use crate::params::parse_list;
+use crate::schedule::{
+    datetime_from_micros, next_after, parse_pattern_or_error, pattern_parameter,
+};
+use crate::single_row::OnceInitData;
+
+#[repr(C)]
+pub(crate) struct NextAcrossZonesBindData {
+    occurrence: Option<i64>,
+    // The zone of the occurrence, null when there is none.
+    zone: *mut String,
+}
+
+impl Free for NextAcrossZonesBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.zone.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.zone));
+        }
+    }
+}
+
+// The earliest occurrence strictly after `from` with the pattern read in the
+// local time of each of `zones`, and the index of the zone it is in. Zones
+// firing at the same instant resolve to the first of them.
+pub(crate) fn next_across_zones(
+    cron: &Cron,
+    from: DateTime<Tz>,
+    zones: &[Tz],
+) -> Option<(usize, DateTime<Tz>)> {
+    zones
+        .iter()
+        .enumerate()
+        .filter_map(|(i, zone)| next_after(cron, from.with_timezone(zone)).map(|x| (i, x)))
+        .min_by_key(|&(i, x)| (x, i))
+}
+
+pub(crate) struct NextAcrossZonesVTab;
+
+impl VTab for NextAcrossZonesVTab {
+    type InitData = OnceInitData;
+    type BindData = NextAcrossZonesBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut NextAcrossZonesBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).occurrence = None;
+        (*data).zone = null_mut();
+        bind.add_result_column("ts", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("zone", LogicalType::new(LogicalTypeId::Varchar));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let from = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &Tz::UTC)
+            .ok_or("Invalid from time")?;
+        let names = parse_list(&bind.get_parameter(2).to_string())?;
+        let zones = names
+            .iter()
+            .map(|name| {
+                name.parse::<Tz>()
+                    .map_err(|_| format!("Invalid or unknown time zone: {}", name))
+            })
+            .collect::<Result<Vec<Tz>, String>>()?;
+
+        if let Some((i, x)) = next_across_zones(&cron, from, &zones) {
+            (*data).occurrence = Some(x.timestamp());
+            (*data).zone = Box::into_raw(Box::new(names[i].clone()));
+        }
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<NextAcrossZonesBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        let mut occurrence = output.flat_vector(0);
+        let mut zone = output.flat_vector(1);
+        match (*bind_info).occurrence {
+            Some(x) => {
+                occurrence.as_mut_slice::<i64>()[0] = x;
+                zone.insert(0, (*(*bind_info).zone).as_str());
+            }
+            None => {
+                occurrence.set_null(0);
+                zone.set_null(0);
+            }
+        }
+        output.set_len(1);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Varchar)),
+        ])
+    }
+}
@@ -0,0 +1,122 @@
+// `cron_next_change(pattern, duration, from)` returns the next instant after
+// `from` at which the schedule switches between running and idle, where each
+// occurrence is considered to run for `duration`.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, search_limit,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct NextChangeBindData {
+    // Seconds since the epoch of the next change, None if there is none
+    // within the search horizon.
+    next_change: Option<i64>,
+}
+
+impl Free for NextChangeBindData {}
+
+// Finds the next time the active state flips after `from`.
+//
+// Windows `[t, t + duration)` that overlap or touch are merged, so a schedule
+// that is continuously busy only reports the end of the merged window.
+pub(crate) fn next_change(
+    cron: &Cron,
+    duration: TimeDelta,
+    from: DateTime<Tz>,
+) -> Option<DateTime<Tz>> {
+    let limit = search_limit(from);
+
+    // Only windows that started after `from - duration` can still cover `from`.
+    let mut occurrences = cron.iter_after(from - duration).take_while(|&x| x <= limit);
+
+    let first = occurrences.next()?;
+    if first > from {
+        // Idle at `from`, so the next change is the next window opening.
+        return Some(first);
+    }
+
+    // Active at `from`, extend the window until a gap appears.
+    let mut end = first + duration;
+    for x in occurrences {
+        if x > end {
+            break;
+        }
+        end = end.max(x + duration);
+    }
+
+    if end > limit {
+        None
+    } else {
+        Some(end)
+    }
+}
+
+pub(crate) struct NextChangeVTab;
+
+impl VTab for NextChangeVTab {
+    type InitData = OnceInitData;
+    type BindData = NextChangeBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut NextChangeBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("next_change", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).next_change = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let duration = parse_interval(&bind.get_parameter(1).to_string())?;
+        if duration <= TimeDelta::zero() {
+            return Err("The duration must be positive".into());
+        }
+        let timezone = timezone_parameter(bind)?;
+        let from = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid from time")?;
+
+        (*data).next_change = next_change(&cron, duration, from).map(|x| x.timestamp());
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<NextChangeBindData>();
+        emit_once(init_info, output, (*bind_info).next_change);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Interval),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
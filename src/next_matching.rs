@@ -0,0 +1,114 @@
+// `cron_next_matching(pattern, from, low, high)` finds the next occurrence
+// within an allowed range of hours, for "next run, but only during business
+// hours" without baking the hours into the pattern.
+
+use chrono::{DateTime, Timelike};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, search_limit,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct NextMatchingBindData {
+    occurrence: Option<i64>,
+}
+
+impl Free for NextMatchingBindData {}
+
+// The first occurrence at or after `from` whose local hour is within
+// `[low, high]`, None if there is none within the search horizon. A range
+// with `low` after `high` wraps around midnight, so `22` to `6` covers the
+// night.
+pub(crate) fn next_matching(
+    cron: &Cron,
+    from: DateTime<Tz>,
+    low: u32,
+    high: u32,
+) -> Option<DateTime<Tz>> {
+    let allowed = |hour: u32| {
+        if low <= high {
+            low <= hour && hour <= high
+        } else {
+            hour >= low || hour <= high
+        }
+    };
+    let limit = search_limit(from);
+    cron.iter_from(from)
+        .take_while(|&x| x <= limit)
+        .find(|x| allowed(x.hour()))
+}
+
+// Reads an hour of the range at positional parameter `index`.
+fn hour_parameter(bind: &BindInfo, index: u64) -> Result<u32, String> {
+    let hour = bind.get_parameter(index).to_int64();
+    u32::try_from(hour)
+        .ok()
+        .filter(|&hour| hour < 24)
+        .ok_or_else(|| format!("Hours must be between 0 and 23, found {}", hour))
+}
+
+pub(crate) struct NextMatchingVTab;
+
+impl VTab for NextMatchingVTab {
+    type InitData = OnceInitData;
+    type BindData = NextMatchingBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut NextMatchingBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).occurrence = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let from = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid from time")?;
+        let low = hour_parameter(bind, 2)?;
+        let high = hour_parameter(bind, 3)?;
+
+        (*data).occurrence = next_matching(&cron, from, low, high).map(|x| x.timestamp());
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<NextMatchingBindData>();
+        emit_once(init_info, output, (*bind_info).occurrence);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Integer),
+            LogicalType::new(LogicalTypeId::Integer),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,326 @@
+// `cron_normalize(pattern)` rewrites a pattern into a canonical form so that
+// patterns with the same meaning can be compared or deduplicated as text,
+// `cron_normalize_batch(patterns)` does so for a whole list, and
+// `cron_round_trip_test(pattern)` checks that the canonical form is stable.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::fields::{parse_fields, Field, FieldKind};
+use crate::params::parse_list;
+use crate::schedule::{check_pattern_text, parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_once, emit_text_once, OnceInitData, TextBindData};
+use crate::validate_batch::ValidateBatchInitData;
+
+// Writes the values of a field as the shortest of a step expression, like
+// `*/15` or `5-50/15`, and a list of ranges, like `1-5,10`.
+fn normalize_values(field: &Field) -> Vec<String> {
+    let values = &field.values;
+    let kind = field.kind;
+    // Sunday is both 0 and 7, so step expressions over weekdays can't be
+    // expanded back reliably.
+    if values.len() >= 3 && kind != FieldKind::DaysOfWeek {
+        let step = values[1] - values[0];
+        let progression = step > 1 && values.windows(2).all(|x| x[1] - x[0] == step);
+        if progression {
+            let (first, last) = (values[0], values[values.len() - 1]);
+            let reaches_end = last + step > kind.max();
+            return vec![match (first == kind.min(), reaches_end) {
+                (true, true) => format!("*/{}", step),
+                (false, true) => format!("{}/{}", first, step),
+                (_, false) => format!("{}-{}/{}", first, last, step),
+            }];
+        }
+    }
+
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j + 1 < values.len() && values[j + 1] == values[j] + 1 {
+            j += 1;
+        }
+        if j > i {
+            items.push(format!("{}-{}", values[i], values[j]));
+        } else {
+            items.push(values[i].to_string());
+        }
+        i = j + 1;
+    }
+    items
+}
+
+// Rewrites one field into its canonical form.
+fn normalize_field(field: &Field) -> String {
+    if field.wildcard {
+        return "*".to_string();
+    }
+    // A day field listing every day still changes how the two day fields
+    // combine in other cron implementations, so only the other fields collapse
+    // into a wildcard.
+    let day_field = matches!(field.kind, FieldKind::DaysOfMonth | FieldKind::DaysOfWeek);
+    let everything = (field.kind.min()..=field.kind.max()).count();
+    if !day_field && field.specials.is_empty() && field.values.len() == everything {
+        return "*".to_string();
+    }
+    let mut items = normalize_values(field);
+    items.extend(field.specials.iter().cloned());
+    items.join(",")
+}
+
+// Rewrites a pattern into its canonical form.
+//
+// Nicknames are expanded, names are replaced by numbers with Sunday as 0,
+// values are sorted and merged into ranges or steps, and a seconds field of
+// `0` is dropped so that five and six field patterns meaning the same thing
+// normalize identically.
+pub(crate) fn normalize(pattern: &str) -> Result<String, String> {
+    parse_pattern_or_error(pattern)?;
+    let mut fields: Vec<String> = parse_fields(pattern)?.iter().map(normalize_field).collect();
+    if fields[0] == "0" {
+        fields.remove(0);
+    }
+    Ok(fields.join(" "))
+}
+
+// Whether normalizing the canonical form of a pattern gives it back unchanged
+// and it still parses, which downstream deduplication relies on.
+pub(crate) fn round_trips(pattern: &str) -> Result<bool, String> {
+    let normalized = normalize(pattern)?;
+    Ok(normalize(&normalized).is_ok_and(|again| again == normalized))
+}
+
+pub(crate) struct NormalizeVTab;
+
+impl VTab for NormalizeVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let pattern = pattern_parameter(bind, 0)?;
+        (*data).set(Some(normalize(&pattern)?));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+}
+
+#[repr(C)]
+pub(crate) struct RoundTripBindData {
+    stable: bool,
+}
+
+impl Free for RoundTripBindData {}
+
+pub(crate) struct RoundTripVTab;
+
+impl VTab for RoundTripVTab {
+    type InitData = OnceInitData;
+    type BindData = RoundTripBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut RoundTripBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("stable", LogicalType::new(LogicalTypeId::Boolean));
+
+        let pattern = pattern_parameter(bind, 0)?;
+        (*data).stable = round_trips(&pattern)?;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<RoundTripBindData>();
+        emit_once(init_info, output, Some((*bind_info).stable));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+}
+
+#[repr(C)]
+pub(crate) struct NormalizeBatchBindData {
+    // Each input, None when NULL, and its canonical form when it is valid.
+    results: *mut Vec<(Option<String>, Option<String>)>,
+}
+
+impl Free for NormalizeBatchBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.results.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.results));
+        }
+    }
+}
+
+pub(crate) struct NormalizeBatchVTab;
+
+impl VTab for NormalizeBatchVTab {
+    type InitData = ValidateBatchInitData;
+    type BindData = NormalizeBatchBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut NormalizeBatchBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).results = null_mut();
+        bind.add_result_column("input", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("normalized", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("valid", LogicalType::new(LogicalTypeId::Boolean));
+
+        let results = parse_list(&bind.get_parameter(0).to_string())?
+            .into_iter()
+            .map(|input| {
+                let normalized = check_pattern_text(&input)
+                    .and_then(|_| normalize(&input))
+                    .ok();
+                let input = if input == "NULL" { None } else { Some(input) };
+                (input, normalized)
+            })
+            .collect();
+        (*data).results = Box::into_raw(Box::new(results));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut ValidateBatchInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).cursor = 0;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<ValidateBatchInitData>();
+        let bind_info = func.get_bind_data::<NormalizeBatchBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let results = &*(*bind_info).results;
+        let start = (*init_info).cursor.min(results.len());
+        let chunk = &results[start..(start + max_items).min(results.len())];
+
+        let mut inputs = output.flat_vector(0);
+        let mut normalized = output.flat_vector(1);
+        let mut valid = output.flat_vector(2);
+        for (row, (input, canonical)) in chunk.iter().enumerate() {
+            match input {
+                Some(input) => inputs.insert(row, input.as_str()),
+                None => inputs.set_null(row),
+            }
+            match canonical {
+                Some(canonical) => normalized.insert(row, canonical.as_str()),
+                None => normalized.set_null(row),
+            }
+            valid.as_mut_slice::<bool>()[row] = canonical.is_some();
+        }
+        output.set_len(chunk.len());
+        (*init_info).cursor = start + chunk.len();
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::list(&LogicalType::new(
+            LogicalTypeId::Varchar,
+        ))])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Patterns written in various ways, used to check the canonical form is
+    // stable.
+    const CORPUS: [&str; 12] = [
+        "* * * * *",
+        "0 0 * * MON",
+        "*/15 * * * *",
+        "0,15,30,45 * * * *",
+        "0 9 * * 1,2,3,4,5",
+        "0 12 * JAN-MAR SAT,SUN",
+        "0 0 1,15 * *",
+        "0 0 L * *",
+        "30 0 9 * * *",
+        "0 0 0 * * *",
+        "@daily",
+        "@weekly",
+    ];
+
+    #[test]
+    fn normalizes_known_patterns() {
+        assert_eq!(normalize("0 0 * * MON").unwrap(), "0 0 * * 1");
+        assert_eq!(normalize("0,15,30,45 * * * *").unwrap(), "*/15 * * * *");
+        assert_eq!(normalize("0 9 * * 1,2,3,4,5").unwrap(), "0 9 * * 1-5");
+        assert_eq!(normalize("0-59 0 * * *").unwrap(), "* 0 * * *");
+        assert_eq!(normalize("0 0 0 * * *").unwrap(), "0 0 * * *");
+        assert_eq!(normalize("30 0 9 * * *").unwrap(), "30 0 9 * * *");
+        assert_eq!(normalize("@daily").unwrap(), "0 0 * * *");
+    }
+
+    #[test]
+    fn equal_schedules_normalize_alike() {
+        assert_eq!(
+            normalize("0 9 * * 1-5").unwrap(),
+            normalize("0 0 9 * * MON-FRI").unwrap()
+        );
+        assert_eq!(
+            normalize("0 0 * * 0").unwrap(),
+            normalize("0 0 * * 7").unwrap()
+        );
+    }
+
+    #[test]
+    fn canonical_forms_round_trip() {
+        for pattern in CORPUS {
+            assert!(round_trips(pattern).unwrap(), "{}", pattern);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// `cron_overlap_minutes(a, b, start, until)` counts how often two schedules
+// fire at the same instant within a window.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    coincident, datetime_from_micros, never_fires, occurrences_between, parse_pattern_or_error,
+    pattern_parameter, timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct OverlapBindData {
+    overlaps: i64,
+}
+
+impl Free for OverlapBindData {}
+
+pub(crate) struct OverlapVTab;
+
+impl VTab for OverlapVTab {
+    type InitData = OnceInitData;
+    type BindData = OverlapBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut OverlapBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("overlaps", LogicalType::new(LogicalTypeId::Bigint));
+
+        let (a_text, b_text) = (pattern_parameter(bind, 0)?, pattern_parameter(bind, 1)?);
+        let a = parse_pattern_or_error(&a_text)?;
+        let b = parse_pattern_or_error(&b_text)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(3).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+
+        // croner searches far ahead for the first occurrence of a pattern that
+        // never fires, and such a pattern overlaps nothing.
+        (*data).overlaps = if never_fires(&a_text) || never_fires(&b_text) {
+            0
+        } else {
+            coincident(
+                occurrences_between(&a, start, until),
+                occurrences_between(&b, start, until),
+            )
+            .count() as i64
+        };
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<OverlapBindData>();
+        emit_once(init_info, output, Some((*bind_info).overlaps));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
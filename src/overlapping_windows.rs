@@ -0,0 +1,157 @@
+// `cron_overlapping_windows(pattern, duration, start, until)` finds the
+// occurrences whose run, lasting `duration`, is still going when the next one
+// starts, so that runs would pile up.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::{duckdb_interval, duckdb_vector_size};
+use std::ptr::null_mut;
+
+use crate::age::to_interval;
+use crate::params::parse_interval;
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, timezone_parameter,
+};
+
+#[repr(C)]
+pub(crate) struct OverlappingWindowsBindData {
+    pattern: *mut Cron,
+    duration: TimeDelta,
+    start: DateTime<Tz>,
+    // Exclusive.
+    until: DateTime<Tz>,
+}
+
+impl Free for OverlappingWindowsBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.pattern.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.pattern));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct OverlappingWindowsInitData {
+    done: bool,
+    // The next occurrence of the last overlap emitted, the search resumes
+    // from it.
+    cursor: Option<DateTime<Tz>>,
+}
+
+impl Free for OverlappingWindowsInitData {}
+
+pub(crate) struct OverlappingWindowsVTab;
+
+impl VTab for OverlappingWindowsVTab {
+    type InitData = OverlappingWindowsInitData;
+    type BindData = OverlappingWindowsBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut OverlappingWindowsBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).pattern = null_mut();
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("next_fire", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("overlap", LogicalType::new(LogicalTypeId::Interval));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        (*data).duration = parse_interval(&bind.get_parameter(1).to_string())?;
+        if (*data).duration <= TimeDelta::zero() {
+            return Err("The duration must be positive".into());
+        }
+        let timezone = timezone_parameter(bind)?;
+        (*data).start = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        (*data).until = datetime_from_micros(bind.get_parameter(3).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        (*data).pattern = Box::into_raw(Box::new(cron));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OverlappingWindowsInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        (*data).cursor = None;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OverlappingWindowsInitData>();
+        let bind_info = func.get_bind_data::<OverlappingWindowsBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let pattern = &*(*bind_info).pattern;
+        let duration = (*bind_info).duration;
+        let until = (*bind_info).until;
+        let from = (*init_info).cursor.unwrap_or((*bind_info).start);
+
+        // The next occurrence of the last one in the window may fall after
+        // `until`, its run still overlaps.
+        let mut occurrences = pattern.iter_from(from);
+        let mut overlaps: Vec<(DateTime<Tz>, DateTime<Tz>, TimeDelta)> = Vec::new();
+        let mut current = occurrences.next();
+        while let Some(x) = current.filter(|&x| x < until) {
+            let next = occurrences.next();
+            if let Some(next) = next {
+                let overlap = x + duration - next;
+                if overlap > TimeDelta::zero() {
+                    overlaps.push((x, next, overlap));
+                    if overlaps.len() == max_items {
+                        break;
+                    }
+                }
+            }
+            current = next;
+        }
+
+        if let Some(&(_, next, _)) = overlaps.last() {
+            (*init_info).cursor = Some(next);
+        }
+        let fires: Vec<i64> = overlaps.iter().map(|(x, _, _)| x.timestamp()).collect();
+        let nexts: Vec<i64> = overlaps.iter().map(|(_, x, _)| x.timestamp()).collect();
+        let amounts: Vec<duckdb_interval> = overlaps
+            .iter()
+            .map(|&(_, _, overlap)| to_interval(overlap))
+            .collect();
+        output.flat_vector(0).copy(&fires);
+        output.flat_vector(1).copy(&nexts);
+        output.flat_vector(2).copy(&amounts);
+        output.set_len(overlaps.len());
+        (*init_info).done = overlaps.len() < max_items;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Interval),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
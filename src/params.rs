@@ -0,0 +1,190 @@
+// Parsing of parameter values that DuckDB only hands over in their VARCHAR
+// rendering.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+// Parses DuckDB's rendering of an INTERVAL, e.g. `1 day 02:30:00` or
+// `-00:05:00`, into a fixed duration.
+//
+// Months and years have no fixed length so they are rejected.
+pub(crate) fn parse_interval(text: &str) -> Result<TimeDelta, String> {
+    match parse_calendar_interval(text)? {
+        (0, fixed) => Ok(fixed),
+        _ => Err(format!(
+            "Intervals containing months or years are not supported: {}",
+            text
+        )),
+    }
+}
+
+// Parses DuckDB's rendering of an INTERVAL into its calendar months (years
+// counted as twelve) and the fixed duration of its days and clock part,
+// e.g. `1 year 2 months 3 days` is `(14, 3 days)`.
+pub(crate) fn parse_calendar_interval(text: &str) -> Result<(i64, TimeDelta), String> {
+    let invalid = || format!("Invalid interval: {}", text);
+    let mut months = 0i64;
+    let mut total = TimeDelta::zero();
+    let mut tokens = text.split_whitespace().peekable();
+
+    while let Some(token) = tokens.next() {
+        if token.contains(':') {
+            let (negative, clock) = match token.strip_prefix('-') {
+                Some(clock) => (true, clock),
+                None => (false, token),
+            };
+            let parts: Vec<&str> = clock.split(':').collect();
+            if parts.len() != 3 {
+                return Err(invalid());
+            }
+            let hours: i64 = parts[0].parse().map_err(|_| invalid())?;
+            let minutes: i64 = parts[1].parse().map_err(|_| invalid())?;
+            let seconds: f64 = parts[2].parse().map_err(|_| invalid())?;
+            let micros = (seconds * 1_000_000.0).round() as i64;
+            let clock = TimeDelta::hours(hours)
+                + TimeDelta::minutes(minutes)
+                + TimeDelta::microseconds(micros);
+            total = total + if negative { -clock } else { clock };
+            continue;
+        }
+
+        let amount: i64 = token.parse().map_err(|_| invalid())?;
+        match tokens.next() {
+            Some("day") | Some("days") => total = total + TimeDelta::days(amount),
+            Some("month") | Some("months") => months += amount,
+            Some("year") | Some("years") => months += amount * 12,
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok((months, total))
+}
+
+// Splits DuckDB's rendering of a LIST, e.g. `[a, b, c]`, into its elements.
+pub(crate) fn parse_list(text: &str) -> Result<Vec<String>, String> {
+    let inner = text
+        .trim()
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| format!("Invalid list: {}", text))?;
+    Ok(split_elements(inner).iter().map(|x| unquote(x)).collect())
+}
+
+// Splits DuckDB's rendering of a STRUCT, e.g. `{'a': 1, 'b': 2}`, into its
+// field names and values.
+pub(crate) fn parse_struct(text: &str) -> Result<Vec<(String, String)>, String> {
+    let invalid = || format!("Invalid struct: {}", text);
+    let inner = text
+        .trim()
+        .strip_prefix('{')
+        .and_then(|rest| rest.strip_suffix('}'))
+        .ok_or_else(invalid)?;
+    split_elements(inner)
+        .iter()
+        .map(|field| {
+            let (name, value) = field.split_once(": ").ok_or_else(invalid)?;
+            Ok((unquote(name), unquote(value)))
+        })
+        .collect()
+}
+
+// Splits the inside of a nested value into its elements.
+//
+// Elements are separated by a comma followed by a space outside of any
+// nested brackets or quotes, so values such as the cron field `0,30` that
+// contain a bare comma survive intact.
+fn split_elements(inner: &str) -> Vec<String> {
+    let mut elements = Vec::new();
+    if inner.trim().is_empty() {
+        return elements;
+    }
+
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    let mut current = String::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' | '"' => quote = Some(c),
+                '[' | '{' | '(' => depth += 1,
+                ']' | '}' | ')' => depth -= 1,
+                ',' if depth == 0 && chars.peek() == Some(&' ') => {
+                    chars.next();
+                    elements.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(c);
+    }
+    elements.push(current.trim().to_string());
+    elements
+}
+
+// Removes the quotes DuckDB may place around a string inside a nested value.
+fn unquote(text: &str) -> String {
+    let text = text.trim();
+    for q in ['\'', '"'] {
+        if text.len() >= 2 && text.starts_with(q) && text.ends_with(q) {
+            return text[1..text.len() - 1].to_string();
+        }
+    }
+    text.to_string()
+}
+
+// Parses DuckDB's rendering of a LIST of integers.
+pub(crate) fn parse_int_list(text: &str) -> Result<Vec<i64>, String> {
+    parse_list(text)?
+        .iter()
+        .map(|x| {
+            x.parse::<i64>()
+                .map_err(|_| format!("Invalid integer in list: {}", x))
+        })
+        .collect()
+}
+
+// Parses DuckDB's rendering of a TIMESTAMP or DATE into microseconds since
+// the epoch.
+pub(crate) fn parse_timestamp_micros(text: &str) -> Result<i64, String> {
+    let text = text.trim();
+    NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d").map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map(|x| x.and_utc().timestamp_micros())
+        .map_err(|_| format!("Invalid timestamp: {}", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_calendar_intervals() {
+        assert_eq!(
+            parse_calendar_interval("10 years"),
+            Ok((120, TimeDelta::zero()))
+        );
+        assert_eq!(
+            parse_calendar_interval("1 year 2 months 3 days 04:00:00"),
+            Ok((14, TimeDelta::days(3) + TimeDelta::hours(4)))
+        );
+        assert_eq!(
+            parse_calendar_interval("3650 days"),
+            Ok((0, TimeDelta::days(3650)))
+        );
+    }
+
+    #[test]
+    fn fixed_intervals_reject_months() {
+        assert_eq!(
+            parse_interval("1 day 02:30:00"),
+            Ok(TimeDelta::minutes(1590))
+        );
+        assert!(parse_interval("1 month").is_err());
+    }
+}
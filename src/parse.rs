@@ -0,0 +1,128 @@
+// `cron_parse(pattern)` exposes the structure of a pattern as a row per
+// field, for schedule inspectors and editors.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use std::ptr::null_mut;
+
+use crate::fields::{expand_nickname, parse_fields, Field, FieldKind};
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::OnceInitData;
+
+#[repr(C)]
+pub(crate) struct ParseBindData {
+    fields: *mut Vec<Field>,
+}
+
+impl Free for ParseBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.fields.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.fields));
+        }
+    }
+}
+
+// The fields of a pattern as it was written, without the seconds field of
+// five field patterns and nicknames.
+fn written_fields(pattern: &str) -> Result<Vec<Field>, String> {
+    let mut fields = parse_fields(pattern)?;
+    let expanded = expand_nickname(pattern).unwrap_or(pattern);
+    if expanded.split_whitespace().count() == 5 {
+        fields.remove(0);
+    }
+    Ok(fields)
+}
+
+pub(crate) struct ParseVTab;
+
+impl VTab for ParseVTab {
+    type InitData = OnceInitData;
+    type BindData = ParseBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut ParseBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).fields = null_mut();
+        bind.add_result_column("field", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("raw", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("min", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("max", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("wildcard", LogicalType::new(LogicalTypeId::Boolean));
+        bind.add_result_column(
+            "values",
+            LogicalType::list(&LogicalType::new(LogicalTypeId::Integer)),
+        );
+
+        let pattern = pattern_parameter(bind, 0)?;
+        parse_pattern_or_error(&pattern)?;
+        (*data).fields = Box::into_raw(Box::new(written_fields(&pattern)?));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<ParseBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        let fields = &*(*bind_info).fields;
+
+        let mut names = output.flat_vector(0);
+        let mut raws = output.flat_vector(1);
+        for (row, field) in fields.iter().enumerate() {
+            names.insert(row, field.kind.name());
+            raws.insert(row, field.raw.as_str());
+        }
+        // Sunday is always reported as 0, so weekdays go up to 6.
+        let mins: Vec<i32> = fields.iter().map(|x| x.kind.min() as i32).collect();
+        let maxes: Vec<i32> = fields
+            .iter()
+            .map(|x| match x.kind {
+                FieldKind::DaysOfWeek => 6,
+                kind => kind.max() as i32,
+            })
+            .collect();
+        let wildcards: Vec<bool> = fields.iter().map(|x| x.wildcard).collect();
+        output.flat_vector(2).copy(&mins);
+        output.flat_vector(3).copy(&maxes);
+        output.flat_vector(4).copy(&wildcards);
+
+        let mut values = output.list_vector(5);
+        let flattened: Vec<i32> = fields
+            .iter()
+            .flat_map(|x| x.values.iter().map(|&value| value as i32))
+            .collect();
+        values.set_child(&flattened);
+        let mut offset = 0;
+        for (row, field) in fields.iter().enumerate() {
+            values.set_entry(row, offset, field.values.len());
+            offset += field.values.len();
+        }
+
+        output.set_len(fields.len());
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+}
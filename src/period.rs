@@ -0,0 +1,135 @@
+// `cron_in_period(pattern, period_start, unit)` emits the occurrences within
+// the day, week, month or year containing an instant, sparing the period
+// arithmetic in SQL.
+
+use chrono::{Datelike, Months, NaiveDate, TimeDelta};
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::align::start_of_day;
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, timezone_parameter,
+};
+use crate::stream::{emit_occurrences, OccurrenceBindData, OccurrenceInitData};
+use crate::weekly::week_start_parameter;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum PeriodUnit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl PeriodUnit {
+    pub(crate) fn parse(unit: &str) -> Result<Self, String> {
+        match unit {
+            "day" => Ok(PeriodUnit::Day),
+            "week" => Ok(PeriodUnit::Week),
+            "month" => Ok(PeriodUnit::Month),
+            "year" => Ok(PeriodUnit::Year),
+            _ => Err(format!(
+                "Invalid unit '{}', expected 'day', 'week', 'month' or 'year'",
+                unit
+            )),
+        }
+    }
+
+    // The first date of the period containing `date` and the first date of
+    // the next one. Weeks start `sunday_offset` days before Sunday.
+    pub(crate) fn dates(
+        &self,
+        date: NaiveDate,
+        sunday_offset: u32,
+    ) -> Option<(NaiveDate, NaiveDate)> {
+        let first = match self {
+            PeriodUnit::Day => date,
+            PeriodUnit::Week => {
+                let into_week = (date.weekday().num_days_from_sunday() + sunday_offset) % 7;
+                date - TimeDelta::days(i64::from(into_week))
+            }
+            PeriodUnit::Month => date.with_day(1)?,
+            PeriodUnit::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1)?,
+        };
+        let next = match self {
+            PeriodUnit::Day => first.succ_opt()?,
+            PeriodUnit::Week => first + TimeDelta::days(7),
+            PeriodUnit::Month => first.checked_add_months(Months::new(1))?,
+            PeriodUnit::Year => first.checked_add_months(Months::new(12))?,
+        };
+        Some((first, next))
+    }
+}
+
+pub(crate) struct InPeriodVTab;
+
+impl VTab for InPeriodVTab {
+    type InitData = OccurrenceInitData;
+    type BindData = OccurrenceBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut OccurrenceBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).init();
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let at = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid period start")?;
+        let unit = PeriodUnit::parse(&bind.get_parameter(2).to_string())?;
+        let sunday_offset = week_start_parameter(bind)?;
+
+        // The period is read on the local calendar, its bounds are the first
+        // instants of its first day and of the next period's.
+        let (first, next) = unit
+            .dates(at.date_naive(), sunday_offset)
+            .ok_or("The period is out of range")?;
+        (*data).start = start_of_day(&timezone, first).ok_or("Invalid period start")?;
+        // Occurrences fall on whole seconds and the last one emitted is
+        // inclusive.
+        (*data).until =
+            start_of_day(&timezone, next).ok_or("Invalid period end")? - TimeDelta::seconds(1);
+
+        (*data).set_pattern(cron);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OccurrenceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).init();
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OccurrenceInitData>();
+        let bind_info = func.get_bind_data::<OccurrenceBindData>();
+        emit_occurrences(bind_info, init_info, output)
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![
+            (
+                "timezone".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "week_starts_on".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+        ])
+    }
+}
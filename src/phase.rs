@@ -0,0 +1,88 @@
+// `cron_phase(pattern, ts)` reports how far `ts` is through the interval
+// between the surrounding occurrences, for progress indicators.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    datetime_from_micros, floor_occurrence, next_after, parse_pattern_or_error, pattern_parameter,
+    timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct PhaseBindData {
+    phase: Option<f64>,
+}
+
+impl Free for PhaseBindData {}
+
+// The fraction of the interval from the occurrence at or before `at` to the
+// one after it that has elapsed, in `[0, 1)`. It is 0 exactly on an
+// occurrence and None when either occurrence is beyond the search horizon.
+pub(crate) fn phase(cron: &Cron, at: DateTime<Tz>) -> Option<f64> {
+    let previous = floor_occurrence(cron, at)?;
+    let next = next_after(cron, at)?;
+    let elapsed = (at - previous).num_milliseconds() as f64;
+    let period = (next - previous).num_milliseconds() as f64;
+    Some(elapsed / period)
+}
+
+pub(crate) struct PhaseVTab;
+
+impl VTab for PhaseVTab {
+    type InitData = OnceInitData;
+    type BindData = PhaseBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut PhaseBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("phase", LogicalType::new(LogicalTypeId::Double));
+        (*data).phase = None;
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let at = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid timestamp")?;
+
+        (*data).phase = phase(&cron, at);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<PhaseBindData>();
+        emit_once(init_info, output, (*bind_info).phase);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,183 @@
+// A tiny English front end compiling phrases like `every 2 hours` or
+// `every weekday at 9am` into cron patterns. It deliberately covers only the
+// simple phrases most schedules are described with:
+//
+//   every minute | every N minutes
+//   every hour | every N hours
+//   every day | every weekday | every weekend | every <day name> [at TIME]
+//   every month [at TIME]
+//
+// where TIME is like `9am`, `9:30 pm`, `17:45`, `noon` or `midnight`.
+
+const DAY_NAMES: [&str; 7] = [
+    "sunday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+];
+
+// Parses a time of day into its hour and minute.
+fn parse_time(text: &str) -> Option<(u32, u32)> {
+    match text {
+        "noon" => return Some((12, 0)),
+        "midnight" => return Some((0, 0)),
+        _ => {}
+    }
+    let (clock, meridiem) = if let Some(clock) = text.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = text.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (text, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour.parse().ok()?, minute.parse().ok()?),
+        Some(_) => return None,
+        // A bare hour is only a time with am or pm.
+        None if meridiem.is_some() => (clock.parse().ok()?, 0),
+        None => return None,
+    };
+    let hour: u32 = match meridiem {
+        Some(pm) if (1..=12).contains(&hour) => hour % 12 + if pm { 12 } else { 0 },
+        Some(_) => return None,
+        None => hour,
+    };
+    if hour < 24 && minute < 60 {
+        Some((hour, minute))
+    } else {
+        None
+    }
+}
+
+// Parses the count of an `every N units` phrase, which must divide the
+// field's cycle: `*/N` restarts at each hour or day, so `*/7` minutes fires
+// at :56 and again four minutes later at :00 rather than every seven.
+fn parse_count(text: &str, unit: &str, cycle: u32) -> Result<Option<u32>, String> {
+    let n: u32 = match text.parse() {
+        Ok(n) if (1..cycle).contains(&n) => n,
+        _ => return Ok(None),
+    };
+    if cycle % n != 0 {
+        return Err(format!(
+            "'every {} {}' can't be written as a cron pattern, since the steps restart every {} {}; use a number of {} that divides {}",
+            n, unit, cycle, unit, unit, cycle
+        ));
+    }
+    Ok(Some(n))
+}
+
+// Compiles a phrase into a five field cron pattern.
+pub(crate) fn compile(phrase: &str) -> Result<String, String> {
+    let unsupported = || {
+        format!(
+            "Unsupported schedule phrase '{}', expected e.g. 'every 15 minutes' or 'every weekday at 9am'",
+            phrase
+        )
+    };
+    let lower = phrase.to_lowercase();
+    let tokens: Vec<&str> = lower.split_whitespace().collect();
+    let rest = match tokens.split_first() {
+        Some((&"every", rest)) if !rest.is_empty() => rest,
+        _ => return Err(unsupported()),
+    };
+
+    // An optional trailing `at TIME`, where `9 am` may be written apart.
+    let (unit, time) = match rest.iter().position(|&x| x == "at") {
+        Some(at) => {
+            let time = parse_time(&rest[at + 1..].concat()).ok_or_else(unsupported)?;
+            (&rest[..at], Some(time))
+        }
+        None => (rest, None),
+    };
+    let (hour, minute) = time.unwrap_or((0, 0));
+
+    let pattern = match unit {
+        ["minute"] if time.is_none() => "* * * * *".to_string(),
+        [n, "minutes"] if time.is_none() => {
+            let n = parse_count(n, "minutes", 60)?.ok_or_else(unsupported)?;
+            format!("*/{} * * * *", n)
+        }
+        ["hour"] if time.is_none() => "0 * * * *".to_string(),
+        [n, "hours"] if time.is_none() => {
+            let n = parse_count(n, "hours", 24)?.ok_or_else(unsupported)?;
+            format!("0 */{} * * *", n)
+        }
+        ["day"] => format!("{} {} * * *", minute, hour),
+        ["weekday"] => format!("{} {} * * 1-5", minute, hour),
+        ["weekend"] => format!("{} {} * * 0,6", minute, hour),
+        ["month"] => format!("{} {} 1 * *", minute, hour),
+        [day] => {
+            let day = DAY_NAMES
+                .iter()
+                .position(|name| *day == *name || day.strip_suffix('s') == Some(name))
+                .ok_or_else(unsupported)?;
+            format!("{} {} * * {}", minute, hour, day)
+        }
+        _ => return Err(unsupported()),
+    };
+    Ok(pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(phrase: &str) -> String {
+        compile(phrase).unwrap()
+    }
+
+    #[test]
+    fn compiles_intervals() {
+        assert_eq!(pattern("every minute"), "* * * * *");
+        assert_eq!(pattern("every 15 minutes"), "*/15 * * * *");
+        assert_eq!(pattern("every 1 minutes"), "*/1 * * * *");
+        assert_eq!(pattern("every 30 minutes"), "*/30 * * * *");
+        assert_eq!(pattern("every hour"), "0 * * * *");
+        assert_eq!(pattern("every 2 hours"), "0 */2 * * *");
+        assert_eq!(pattern("every 12 hours"), "0 */12 * * *");
+    }
+
+    #[test]
+    fn compiles_days() {
+        assert_eq!(pattern("every day"), "0 0 * * *");
+        assert_eq!(pattern("every day at 9am"), "0 9 * * *");
+        assert_eq!(pattern("every weekday at 9:30 pm"), "30 21 * * 1-5");
+        assert_eq!(pattern("every weekend at noon"), "0 12 * * 0,6");
+        assert_eq!(pattern("every month at midnight"), "0 0 1 * *");
+        assert_eq!(pattern("every sunday"), "0 0 * * 0");
+        assert_eq!(pattern("every Fridays at 17:45"), "45 17 * * 5");
+        assert_eq!(pattern("every monday at 12am"), "0 0 * * 1");
+        assert_eq!(pattern("every tuesday at 12pm"), "0 12 * * 2");
+    }
+
+    #[test]
+    fn rejects_steps_that_dont_divide_the_cycle() {
+        for phrase in ["every 7 minutes", "every 45 minutes", "every 5 hours"] {
+            let err = compile(phrase).unwrap_err();
+            assert!(err.contains("divides"), "{}: {}", phrase, err);
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_phrases() {
+        for phrase in [
+            "",
+            "daily",
+            "every",
+            "every 0 minutes",
+            "every 60 minutes",
+            "every 24 hours",
+            "every 15 minutes at 9am",
+            "every day at 25:00",
+            "every day at 13pm",
+            "every day at 9",
+            "every fortnight",
+        ] {
+            let err = compile(phrase).unwrap_err();
+            assert!(err.starts_with("Unsupported"), "{}: {}", phrase, err);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+// The precision of the timestamps produced by `cron()`.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use duckdb::vtab::LogicalTypeId;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Precision {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+    // Microseconds as a TIMESTAMP WITH TIME ZONE, an instant DuckDB displays
+    // in the session's zone.
+    TimeZoned,
+}
+
+impl Precision {
+    pub(crate) fn parse(precision: &str) -> Result<Self, String> {
+        match precision {
+            "s" => Ok(Precision::Seconds),
+            "ms" => Ok(Precision::Milliseconds),
+            "us" => Ok(Precision::Microseconds),
+            "ns" => Ok(Precision::Nanoseconds),
+            "tz" => Ok(Precision::TimeZoned),
+            _ => Err(format!(
+                "Invalid precision '{}', expected 's', 'ms', 'us', 'ns' or 'tz'",
+                precision
+            )),
+        }
+    }
+
+    // The DuckDB timestamp type storing values of this precision.
+    pub(crate) fn logical_type_id(&self) -> LogicalTypeId {
+        match self {
+            Precision::Seconds => LogicalTypeId::TimestampS,
+            Precision::Milliseconds => LogicalTypeId::TimestampMs,
+            Precision::Microseconds => LogicalTypeId::Timestamp,
+            Precision::Nanoseconds => LogicalTypeId::TimestampNs,
+            Precision::TimeZoned => LogicalTypeId::TimestampTZ,
+        }
+    }
+
+    // Converts an occurrence into the integer stored in the output vector.
+    //
+    // Nanoseconds since the epoch overflow an i64 after 2262-04-11, which is
+    // reported as an error rather than wrapping.
+    pub(crate) fn convert(&self, x: &DateTime<Tz>) -> Result<i64, String> {
+        match self {
+            Precision::Seconds => Ok(x.timestamp()),
+            Precision::Milliseconds => Ok(x.timestamp_millis()),
+            Precision::Microseconds | Precision::TimeZoned => Ok(x.timestamp_micros()),
+            Precision::Nanoseconds => x.timestamp_nanos_opt().ok_or_else(|| {
+                format!(
+                    "The occurrence {} cannot be represented with nanosecond precision",
+                    x
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::America::New_York;
+
+    #[test]
+    fn time_zoned_values_are_instants_across_dst() {
+        // Both 01:30 of November 3rd 2024 in New York, EDT then EST.
+        let (first, second) = match New_York.with_ymd_and_hms(2024, 11, 3, 1, 30, 0) {
+            chrono::LocalResult::Ambiguous(first, second) => (first, second),
+            _ => panic!("01:30 should be repeated"),
+        };
+        let micros = |x| Precision::TimeZoned.convert(&x).unwrap();
+        assert_eq!(micros(first), 1_730_611_800_000_000);
+        assert_eq!(micros(second) - micros(first), 3_600_000_000);
+
+        // Local midnights either side of spring forward are 23 hours apart.
+        let midnight = |day| New_York.with_ymd_and_hms(2024, 3, day, 0, 0, 0).unwrap();
+        assert_eq!(
+            micros(midnight(11)) - micros(midnight(10)),
+            23 * 3_600_000_000
+        );
+    }
+
+    #[test]
+    fn precisions_scale_the_epoch() {
+        let x = Tz::UTC.with_ymd_and_hms(2024, 6, 1, 9, 0, 30).unwrap();
+        assert_eq!(Precision::Seconds.convert(&x), Ok(1_717_232_430));
+        assert_eq!(Precision::Milliseconds.convert(&x), Ok(1_717_232_430_000));
+        assert_eq!(
+            Precision::Microseconds.convert(&x),
+            Ok(1_717_232_430_000_000)
+        );
+        assert_eq!(
+            Precision::Nanoseconds.convert(&x),
+            Ok(1_717_232_430_000_000_000)
+        );
+        let far = Tz::UTC.with_ymd_and_hms(2300, 1, 1, 0, 0, 0).unwrap();
+        assert!(Precision::Nanoseconds.convert(&far).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+// `cron_preview(pattern, count)` lists the next occurrences with countdowns as
+// a single text, for embedding schedule previews in notifications and logs.
+
+use chrono::{DateTime, Local};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::humanize::countdown;
+use crate::schedule::{
+    datetime_from_micros, next_after, parse_pattern_or_error, pattern_parameter, timezone_parameter,
+};
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+// Lists the next `count` occurrences after `from`, one per line like
+// `2024-05-01 09:00 (in 2h)`, None if there is none within the search horizon.
+pub(crate) fn preview(cron: &Cron, from: DateTime<Tz>, count: usize) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut at = from;
+    while lines.len() < count {
+        match next_after(cron, at) {
+            Some(next) => {
+                lines.push(format!(
+                    "{} ({})",
+                    next.format("%Y-%m-%d %H:%M"),
+                    countdown(next - from)
+                ));
+                at = next;
+            }
+            None => break,
+        }
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+pub(crate) struct PreviewVTab;
+
+impl VTab for PreviewVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("preview", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let count = bind.get_parameter(1).to_int64();
+        if count < 1 {
+            return Err(format!("The count must be positive, found {}", count).into());
+        }
+        let timezone = timezone_parameter(bind)?;
+        let from: DateTime<Tz> = match bind.get_named_parameter("from") {
+            Some(value) => datetime_from_micros(value.to_int64_timestamp(), &timezone)
+                .ok_or("Invalid from time")?,
+            None => Local::now().with_timezone(&timezone),
+        };
+
+        (*data).set(preview(&cron, from, count as usize));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Bigint),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![
+            (
+                "from".to_string(),
+                LogicalType::new(LogicalTypeId::Timestamp),
+            ),
+            (
+                "timezone".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,517 @@
+// Helpers shared by the table functions for parsing patterns and walking
+// their occurrences.
+
+use chrono::{DateTime, TimeDelta, TimeZone};
+use chrono_tz::Tz;
+use croner::{errors::CronError, Cron};
+use duckdb::vtab::BindInfo;
+use std::cmp::Ordering;
+
+use crate::fields::{impossible_date, parse_fields};
+
+// How far into the future searches for a single occurrence or boundary are
+// allowed to look before giving up.
+pub(crate) const SEARCH_HORIZON_DAYS: i64 = 366 * 5;
+
+// The calendar repeats its weekday/date alignment every 28 years between
+// 1901 and 2099, so sampling 2001 through 2028 sees every combination of
+// fields a pattern can produce.
+const SAMPLE_START_YEAR: i32 = 2001;
+const SAMPLE_YEARS: i32 = 28;
+
+// How a pattern restricting both the day-of-month and day-of-week fields
+// combines them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum DayCombination {
+    // Both fields must match, so `0 0 13 * 5` is Friday the 13th.
+    And,
+    // Either field may match, as in Unix cron, so `0 0 13 * 5` is every 13th
+    // and every Friday.
+    Or,
+}
+
+impl DayCombination {
+    pub(crate) fn parse(dom_dow: &str) -> Result<Self, String> {
+        match dom_dow {
+            "and" => Ok(DayCombination::And),
+            "or" => Ok(DayCombination::Or),
+            _ => Err(format!(
+                "Invalid dom_dow '{}', expected 'and' or 'or'",
+                dom_dow
+            )),
+        }
+    }
+}
+
+// Parses a cron pattern the same way for every function in the extension.
+pub(crate) fn parse_pattern(pattern: &str) -> Result<Cron, CronError> {
+    parse_pattern_combining(pattern, DayCombination::And)
+}
+
+// Parses a cron pattern combining its day fields as given.
+pub(crate) fn parse_pattern_combining(
+    pattern: &str,
+    days: DayCombination,
+) -> Result<Cron, CronError> {
+    let mut cron = Cron::new(pattern);
+    cron.with_seconds_optional();
+    if days == DayCombination::And {
+        cron.with_dom_and_dow();
+    }
+    cron.parse()
+}
+
+// Parses a cron pattern, turning a failure into the error reported to DuckDB.
+pub(crate) fn parse_pattern_or_error(pattern: &str) -> Result<Cron, String> {
+    parse_pattern(pattern).map_err(|err| format!("Failed to parse cron expression: {}", err))
+}
+
+// Explains how a six field pattern is interpreted when it looks like it was
+// written for another layout.
+//
+// Seconds are optional, so a six field pattern is read seconds first. That
+// is only ambiguous when the last field looks like the year of a five field
+// pattern, or a `?` day marks a Quartz pattern, whose weekdays are numbered
+// from 1 for Sunday, with numeric weekdays.
+pub(crate) fn field_count_ambiguity(pattern: &str) -> Option<String> {
+    let fields: Vec<&str> = pattern.split_whitespace().collect();
+    if fields.len() != 6 {
+        return None;
+    }
+    let weekdays: Vec<u32> = fields[5]
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|x| x.parse().ok())
+        .collect();
+    if !weekdays.is_empty() && weekdays.iter().all(|&x| x >= 1970) {
+        return Some(format!(
+            "The cron pattern '{}' has 6 fields and is interpreted as \
+             'second minute hour day-of-month month day-of-week', \
+             but its last field looks like the year of a five field pattern",
+            pattern
+        ));
+    }
+    if (fields[3] == "?" || fields[5] == "?") && !weekdays.is_empty() {
+        return Some(format!(
+            "The cron pattern '{}' uses '?' like a Quartz pattern, but its \
+             weekdays are numbered from 0 for Sunday, not from 1 as in Quartz",
+            pattern
+        ));
+    }
+    None
+}
+
+// Whether a pattern can never fire because its dates do not exist.
+//
+// croner only gives up on such a pattern after searching thousands of years
+// ahead, so bounded generation checks for them up front. When both day fields
+// must match an impossible date can never be satisfied.
+pub(crate) fn never_fires(pattern: &str) -> bool {
+    never_fires_combining(pattern, DayCombination::And)
+}
+
+// Whether a pattern combining its day fields as given can never fire. When
+// either may match, a restricted weekday still fires on impossible dates.
+pub(crate) fn never_fires_combining(pattern: &str, days: DayCombination) -> bool {
+    match parse_fields(pattern) {
+        Ok(fields) => {
+            impossible_date(&fields) && (days == DayCombination::And || fields[5].wildcard)
+        }
+        Err(_) => false,
+    }
+}
+
+// Reads the pattern passed as the positional parameter at `index`.
+//
+// A NULL pattern, e.g. from a column that turned out to be NULL, reaches
+// bind as the text `NULL` and would otherwise be reported as a confusing
+// parse error.
+pub(crate) fn pattern_parameter(bind: &BindInfo, index: u64) -> Result<String, String> {
+    let value = bind.get_parameter(index).to_string();
+    check_pattern_text(&value)?;
+    Ok(value)
+}
+
+// Rejects DuckDB's rendering of a NULL pattern and empty patterns.
+pub(crate) fn check_pattern_text(value: &str) -> Result<(), String> {
+    match value.trim() {
+        "NULL" => Err("cron pattern must not be NULL".to_string()),
+        "" => Err("cron pattern must not be empty".to_string()),
+        _ => Ok(()),
+    }
+}
+
+// Parses the time zone occurrences are evaluated in, UTC when none is given.
+//
+// There is no session default to fall back on: DuckDB doesn't pass its
+// `TimeZone` setting to table functions, so results never depend on the
+// session.
+pub(crate) fn parse_timezone(timezone: Option<&str>) -> Result<Tz, String> {
+    match timezone {
+        Some(timezone) => timezone
+            .parse()
+            .map_err(|_| "Invalid or unknown time zone".to_string()),
+        None => Ok(Tz::UTC),
+    }
+}
+
+// The optional `timezone` named parameter, as given.
+pub(crate) fn timezone_name(bind: &BindInfo) -> Option<String> {
+    bind.get_named_parameter("timezone")
+        .map(|value| value.to_string())
+}
+
+// Resolves the optional `timezone` named parameter, defaulting to UTC.
+pub(crate) fn timezone_parameter(bind: &BindInfo) -> Result<Tz, String> {
+    parse_timezone(timezone_name(bind).as_deref())
+}
+
+// Converts a DuckDB TIMESTAMP (microseconds since the epoch) into a time in
+// the given timezone.
+pub(crate) fn datetime_from_micros(micros: i64, timezone: &Tz) -> Option<DateTime<Tz>> {
+    DateTime::from_timestamp(micros.div_euclid(1_000_000), 0).map(|x| x.with_timezone(timezone))
+}
+
+// Converts a DuckDB TIMESTAMP into a bound of a window of occurrences.
+//
+// Occurrences fall on whole seconds while bounds may have a fractional part,
+// so bounds are rounded up: an occurrence is at or after `09:00:30.5` exactly
+// when it is at or after `09:00:31`, and likewise before.
+pub(crate) fn bound_from_micros(micros: i64, timezone: &Tz) -> Option<DateTime<Tz>> {
+    let seconds = micros.div_euclid(1_000_000) + i64::from(micros.rem_euclid(1_000_000) > 0);
+    DateTime::from_timestamp(seconds, 0).map(|x| x.with_timezone(timezone))
+}
+
+// The first occurrence strictly after `from` within the search horizon.
+pub(crate) fn next_after(cron: &Cron, from: DateTime<Tz>) -> Option<DateTime<Tz>> {
+    cron.iter_after(from)
+        .next()
+        .filter(|&x| x <= search_limit(from))
+}
+
+// The last occurrence at or before `at` within the search horizon.
+//
+// croner only searches forwards, so windows of growing size ending at `at`
+// are scanned until one contains an occurrence.
+pub(crate) fn floor_occurrence(cron: &Cron, at: DateTime<Tz>) -> Option<DateTime<Tz>> {
+    let horizon = TimeDelta::days(SEARCH_HORIZON_DAYS);
+    let mut window = TimeDelta::minutes(1);
+    loop {
+        let latest = cron.iter_from(at - window).take_while(|&x| x <= at).last();
+        if latest.is_some() || window >= horizon {
+            return latest;
+        }
+        window = (window * 8).min(horizon);
+    }
+}
+
+// The occurrences of `cron` in the half open window `[start, until)`.
+pub(crate) fn occurrences_between(
+    cron: &Cron,
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+) -> impl Iterator<Item = DateTime<Tz>> + '_ {
+    cron.iter_from(start).take_while(move |&x| x < until)
+}
+
+// The instants present in both of two ascending streams of occurrences.
+pub(crate) fn coincident<'a>(
+    a: impl Iterator<Item = DateTime<Tz>> + 'a,
+    b: impl Iterator<Item = DateTime<Tz>> + 'a,
+) -> impl Iterator<Item = DateTime<Tz>> + 'a {
+    let mut a = a.peekable();
+    let mut b = b.peekable();
+    std::iter::from_fn(move || loop {
+        let (x, y) = (*a.peek()?, *b.peek()?);
+        match x.cmp(&y) {
+            Ordering::Less => {
+                a.next();
+            }
+            Ordering::Greater => {
+                b.next();
+            }
+            Ordering::Equal => {
+                a.next();
+                b.next();
+                return Some(x);
+            }
+        }
+    })
+}
+
+// Merges ascending streams of occurrences into a single ascending stream,
+// tagging each occurrence with the index of the stream it came from.
+// Coincident occurrences are produced in ascending stream order.
+pub(crate) fn merge_occurrences<'a>(
+    streams: Vec<Box<dyn Iterator<Item = DateTime<Tz>> + 'a>>,
+) -> impl Iterator<Item = (usize, DateTime<Tz>)> + 'a {
+    let mut streams: Vec<_> = streams.into_iter().map(|x| x.peekable()).collect();
+    std::iter::from_fn(move || {
+        let mut best: Option<(usize, DateTime<Tz>)> = None;
+        for (i, stream) in streams.iter_mut().enumerate() {
+            if let Some(&x) = stream.peek() {
+                let earlier = match best {
+                    Some((_, earliest)) => x < earliest,
+                    None => true,
+                };
+                if earlier {
+                    best = Some((i, x));
+                }
+            }
+        }
+        let (i, x) = best?;
+        streams[i].next();
+        Some((i, x))
+    })
+}
+
+// Drops repeated instants from an ascending stream of tagged occurrences,
+// keeping the first of them.
+pub(crate) fn dedup_sorted<'a>(
+    occurrences: impl Iterator<Item = (usize, DateTime<Tz>)> + 'a,
+) -> impl Iterator<Item = (usize, DateTime<Tz>)> + 'a {
+    let mut last = None;
+    occurrences.filter(move |&(_, x)| {
+        let repeated = last == Some(x);
+        last = Some(x);
+        !repeated
+    })
+}
+
+// The representative window functions sample when a property of a pattern
+// cannot be derived from its fields alone.
+pub(crate) fn sample_window(timezone: &Tz) -> Result<(DateTime<Tz>, DateTime<Tz>), String> {
+    let boundary = |year| {
+        timezone
+            .with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+            .earliest()
+            .ok_or_else(|| "Unable to sample the time zone".to_string())
+    };
+    Ok((
+        boundary(SAMPLE_START_YEAR)?,
+        boundary(SAMPLE_START_YEAR + SAMPLE_YEARS)?,
+    ))
+}
+
+// The latest instant a search starting at `from` may consider.
+pub(crate) fn search_limit(from: DateTime<Tz>) -> DateTime<Tz> {
+    from + TimeDelta::days(SEARCH_HORIZON_DAYS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_patterns_are_not_ambiguous() {
+        assert_eq!(field_count_ambiguity("0 9 * * 1-5"), None);
+        assert_eq!(field_count_ambiguity("*/10 * * * * *"), None);
+        assert_eq!(field_count_ambiguity("30 0 9 * * 1-5"), None);
+        assert_eq!(field_count_ambiguity("0 0 12 ? * MON-FRI"), None);
+    }
+
+    #[test]
+    fn trailing_years_are_ambiguous() {
+        assert!(field_count_ambiguity("0 9 * * 1 2024").is_some());
+        assert!(field_count_ambiguity("0 9 1 1 * 2025-2027").is_some());
+    }
+
+    #[test]
+    fn quartz_weekday_numbers_are_ambiguous() {
+        assert!(field_count_ambiguity("0 0 12 ? * 2-6").is_some());
+        assert!(field_count_ambiguity("0 0 12 ? * 1").is_some());
+    }
+
+    #[test]
+    fn timezone_defaults_to_utc() {
+        assert_eq!(parse_timezone(None), Ok(Tz::UTC));
+    }
+
+    #[test]
+    fn explicit_timezones_are_parsed() {
+        assert_eq!(
+            parse_timezone(Some("America/New_York")),
+            Ok(Tz::America__New_York)
+        );
+        assert_eq!(parse_timezone(Some("UTC")), Ok(Tz::UTC));
+        assert!(parse_timezone(Some("America/Nowhere")).is_err());
+        assert!(parse_timezone(Some("")).is_err());
+    }
+
+    #[test]
+    fn parses_day_combinations() {
+        assert_eq!(DayCombination::parse("and"), Ok(DayCombination::And));
+        assert_eq!(DayCombination::parse("or"), Ok(DayCombination::Or));
+        assert!(DayCombination::parse("AND").is_err());
+        assert!(DayCombination::parse("").is_err());
+    }
+
+    // The days of 2024 on which a pattern fires.
+    fn days_in_2024(pattern: &str, days: DayCombination) -> Vec<String> {
+        let cron = parse_pattern_combining(pattern, days).unwrap();
+        let start = Tz::UTC.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let until = Tz::UTC.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        occurrences_between(&cron, start, until)
+            .map(|x| x.format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn and_requires_both_day_fields() {
+        assert_eq!(
+            days_in_2024("0 0 13 * 5", DayCombination::And),
+            vec!["2024-09-13", "2024-12-13"]
+        );
+    }
+
+    #[test]
+    fn or_accepts_either_day_field() {
+        let days = days_in_2024("0 0 13 * 5", DayCombination::Or);
+        // Twelve 13ths and 52 Fridays, two of them both.
+        assert_eq!(days.len(), 62);
+        assert!(days.contains(&"2024-01-13".to_string()));
+        assert!(days.contains(&"2024-01-05".to_string()));
+        assert!(days.contains(&"2024-09-13".to_string()));
+        assert!(!days.contains(&"2024-01-06".to_string()));
+    }
+
+    #[test]
+    fn unrestricted_days_combine_alike() {
+        assert_eq!(
+            days_in_2024("0 0 13 * *", DayCombination::And),
+            days_in_2024("0 0 13 * *", DayCombination::Or)
+        );
+        assert_eq!(
+            days_in_2024("0 0 * * 5", DayCombination::And),
+            days_in_2024("0 0 * * 5", DayCombination::Or)
+        );
+    }
+
+    #[test]
+    fn impossible_dates_never_fire() {
+        assert!(never_fires("0 0 31 4 *"));
+        assert!(never_fires("0 0 30 2 *"));
+        assert!(never_fires("0 0 30,31 2 *"));
+        assert!(never_fires("0 0 0 31 4,6,9,11 *"));
+        assert!(!never_fires("0 0 29 2 *"));
+        assert!(!never_fires("0 0 31 4,5 *"));
+        assert!(!never_fires("0 0 L 2 *"));
+        assert!(!never_fires("0 0 * * *"));
+    }
+
+    #[test]
+    fn weekdays_fire_on_impossible_dates_when_ored() {
+        assert!(never_fires_combining("0 0 31 4 1", DayCombination::And));
+        assert!(!never_fires_combining("0 0 31 4 1", DayCombination::Or));
+        assert!(never_fires_combining("0 0 31 4 *", DayCombination::Or));
+    }
+
+    #[test]
+    fn null_and_empty_patterns_are_rejected() {
+        assert_eq!(
+            check_pattern_text("NULL"),
+            Err("cron pattern must not be NULL".to_string())
+        );
+        assert_eq!(
+            check_pattern_text(""),
+            Err("cron pattern must not be empty".to_string())
+        );
+        assert_eq!(
+            check_pattern_text("  \t "),
+            Err("cron pattern must not be empty".to_string())
+        );
+        assert!(check_pattern_text("0 9 * * *").is_ok());
+        assert!(check_pattern_text(" @daily ").is_ok());
+    }
+
+    // Microseconds since the epoch of a time on June 1st 2024, UTC.
+    fn june_first_micros(hour: u32, minute: u32, second: u32, micros: i64) -> i64 {
+        Tz::UTC
+            .with_ymd_and_hms(2024, 6, 1, hour, minute, second)
+            .unwrap()
+            .timestamp()
+            * 1_000_000
+            + micros
+    }
+
+    #[test]
+    fn fractional_bounds_round_up() {
+        let bound = |micros| bound_from_micros(micros, &Tz::UTC).unwrap();
+        let at = |second| Tz::UTC.with_ymd_and_hms(2024, 6, 1, 9, 0, second).unwrap();
+        assert_eq!(bound(june_first_micros(9, 0, 29, 500_000)), at(30));
+        assert_eq!(bound(june_first_micros(9, 0, 30, 0)), at(30));
+        assert_eq!(bound(june_first_micros(9, 0, 30, 1)), at(31));
+        assert_eq!(bound(-1), Tz::UTC.timestamp_opt(0, 0).unwrap());
+    }
+
+    #[test]
+    fn seconds_fields_survive_fractional_bounds() {
+        let cron = parse_pattern("30 0 9 * * *").unwrap();
+        let bound = |micros| bound_from_micros(micros, &Tz::UTC).unwrap();
+        let fires = |start, until| -> Vec<i64> {
+            occurrences_between(&cron, bound(start), bound(until))
+                .map(|x| x.timestamp())
+                .collect()
+        };
+        let occurrence = june_first_micros(9, 0, 30, 0) / 1_000_000;
+        assert_eq!(occurrence % 60, 30);
+
+        // 09:00:29.5 to 09:00:30.5 includes 09:00:30.
+        assert_eq!(
+            fires(
+                june_first_micros(9, 0, 29, 500_000),
+                june_first_micros(9, 0, 30, 500_000)
+            ),
+            vec![occurrence]
+        );
+        // Until is exclusive, and a start after it excludes it.
+        assert!(fires(
+            june_first_micros(9, 0, 0, 0),
+            june_first_micros(9, 0, 30, 0)
+        )
+        .is_empty());
+        assert!(fires(
+            june_first_micros(9, 0, 30, 1),
+            june_first_micros(9, 1, 0, 0)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn coincident_occurrences_merge_in_pattern_order() {
+        let hourly = parse_pattern("0 * * * *").unwrap();
+        let half_hourly = parse_pattern("*/30 * * * *").unwrap();
+        let start = Tz::UTC.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap();
+        let until = start + TimeDelta::minutes(90);
+        let merged = || {
+            let streams: Vec<Box<dyn Iterator<Item = DateTime<Tz>>>> = vec![
+                Box::new(occurrences_between(&hourly, start, until)),
+                Box::new(occurrences_between(&half_hourly, start, until)),
+            ];
+            merge_occurrences(streams)
+        };
+        let minutes = |rows: Vec<(usize, DateTime<Tz>)>| -> Vec<(usize, i64)> {
+            rows.iter()
+                .map(|&(i, x)| (i, (x - start).num_minutes()))
+                .collect()
+        };
+
+        // Every source of an instant, by ascending source index.
+        assert_eq!(
+            minutes(merged().collect()),
+            vec![(0, 0), (1, 0), (1, 30), (0, 60), (1, 60)]
+        );
+        // A single row per instant, from the first source.
+        assert_eq!(
+            minutes(dedup_sorted(merged()).collect()),
+            vec![(0, 0), (1, 30), (0, 60)]
+        );
+        // The instants both fire at.
+        let shared: Vec<i64> = coincident(
+            occurrences_between(&hourly, start, until),
+            occurrences_between(&half_hourly, start, until),
+        )
+        .map(|x| (x - start).num_minutes())
+        .collect();
+        assert_eq!(shared, vec![0, 60]);
+    }
+}
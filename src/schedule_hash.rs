@@ -0,0 +1,96 @@
+// `cron_schedule_hash(pattern, start, until)` digests the occurrences of a
+// pattern within a window, so a cached schedule can be reused until it
+// effectively changes.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::hashed::{fnv1a, FNV_OFFSET_BASIS};
+use crate::schedule::{
+    datetime_from_micros, never_fires, occurrences_between, parse_pattern_or_error,
+    pattern_parameter, timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+#[repr(C)]
+pub(crate) struct ScheduleHashBindData {
+    hash: i64,
+}
+
+impl Free for ScheduleHashBindData {}
+
+// Folds the occurrences in `[start, until)`, as seconds since the epoch in
+// little endian, into a hash. Only the instants matter, so patterns written
+// differently but firing at the same times hash the same.
+pub(crate) fn schedule_hash(cron: &Cron, start: DateTime<Tz>, until: DateTime<Tz>) -> u64 {
+    occurrences_between(cron, start, until).fold(FNV_OFFSET_BASIS, |hash, x| {
+        fnv1a(hash, &x.timestamp().to_le_bytes())
+    })
+}
+
+pub(crate) struct ScheduleHashVTab;
+
+impl VTab for ScheduleHashVTab {
+    type InitData = OnceInitData;
+    type BindData = ScheduleHashBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut ScheduleHashBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("hash", LogicalType::new(LogicalTypeId::Bigint));
+
+        let pattern = pattern_parameter(bind, 0)?;
+        let cron = parse_pattern_or_error(&pattern)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+
+        let hash = if never_fires(&pattern) {
+            FNV_OFFSET_BASIS
+        } else {
+            schedule_hash(&cron, start, until)
+        };
+        (*data).hash = hash as i64;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<ScheduleHashBindData>();
+        emit_once(init_info, output, Some((*bind_info).hash));
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,88 @@
+// `cron_with_seconds(pattern, seconds)` converts between the five field and
+// the explicit six field (seconds first) forms of a pattern.
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+// Injects `seconds` as the leading field of `pattern`, replacing an existing
+// seconds field. An empty `seconds` strips the seconds field instead, which is
+// only possible when it is `0` since five field patterns fire on the minute.
+pub(crate) fn with_seconds(pattern: &str, seconds: &str) -> Result<String, String> {
+    let fields: Vec<&str> = pattern.split_whitespace().collect();
+    let rest = match fields.len() {
+        5 => &fields[..],
+        6 => &fields[1..],
+        count => {
+            return Err(format!(
+                "Expected a cron pattern with 5 or 6 fields, found {}",
+                count
+            ))
+        }
+    };
+
+    let seconds = seconds.trim();
+    let result = if seconds.is_empty() {
+        if fields.len() == 6 && fields[0] != "0" {
+            return Err(format!(
+                "The seconds field '{}' cannot be removed without changing the schedule",
+                fields[0]
+            ));
+        }
+        rest.join(" ")
+    } else {
+        if seconds.split_whitespace().count() != 1 {
+            return Err(format!("Invalid seconds field: {}", seconds));
+        }
+        format!("{} {}", seconds, rest.join(" "))
+    };
+
+    parse_pattern_or_error(&result)?;
+    Ok(result)
+}
+
+pub(crate) struct WithSecondsVTab;
+
+impl VTab for WithSecondsVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let pattern = pattern_parameter(bind, 0)?;
+        let seconds = bind.get_parameter(1).to_string();
+        (*data).set(Some(with_seconds(&pattern, &seconds)?));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+}
@@ -0,0 +1,139 @@
+// Moving the occurrences of `cron()` by a fixed offset, e.g. to model a job
+// that starts a while after its trigger.
+
+use chrono::{DateTime, LocalResult, TimeDelta, TimeZone};
+use chrono_tz::Tz;
+
+// How a shift crossing a daylight saving transition is measured.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum ShiftMode {
+    // Move the local clock time, so a one hour shift of 01:30 is 02:30 local
+    // time even if the clocks fell back in between.
+    WallClock,
+    // Add the duration to the instant, so the same shift can land on 01:30
+    // again when the clocks fell back at 02:00.
+    Absolute,
+}
+
+impl ShiftMode {
+    pub(crate) fn parse(mode: &str) -> Result<Self, String> {
+        match mode {
+            "wallclock" => Ok(ShiftMode::WallClock),
+            "absolute" => Ok(ShiftMode::Absolute),
+            _ => Err(format!(
+                "Invalid shift_mode '{}', expected 'wallclock' or 'absolute'",
+                mode
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Shift {
+    pub(crate) amount: TimeDelta,
+    pub(crate) mode: ShiftMode,
+}
+
+impl Shift {
+    // Moves an occurrence by the shift.
+    //
+    // A wall clock shift landing on a local time that happens twice takes its
+    // first occurrence, and one landing on a local time skipped by the
+    // clocks springing forward is added to the instant instead.
+    pub(crate) fn apply(&self, x: DateTime<Tz>) -> DateTime<Tz> {
+        match self.mode {
+            ShiftMode::Absolute => x + self.amount,
+            ShiftMode::WallClock => {
+                match x
+                    .timezone()
+                    .from_local_datetime(&(x.naive_local() + self.amount))
+                {
+                    LocalResult::Single(y) | LocalResult::Ambiguous(y, _) => y,
+                    LocalResult::None => x + self.amount,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::America::New_York;
+
+    // An instant given in UTC, in New York.
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Tz> {
+        Tz::UTC
+            .with_ymd_and_hms(2024, month, day, hour, minute, 0)
+            .unwrap()
+            .with_timezone(&New_York)
+    }
+
+    fn shift(x: DateTime<Tz>, amount: TimeDelta, mode: ShiftMode) -> DateTime<Tz> {
+        Shift { amount, mode }.apply(x)
+    }
+
+    #[test]
+    fn fall_back_modes_differ() {
+        // The first 01:30 of November 3rd, still EDT.
+        let x = utc(11, 3, 5, 30);
+        // 02:30 EST.
+        assert_eq!(
+            shift(x, TimeDelta::hours(1), ShiftMode::WallClock),
+            utc(11, 3, 7, 30)
+        );
+        // The second 01:30, now EST.
+        assert_eq!(
+            shift(x, TimeDelta::hours(1), ShiftMode::Absolute),
+            utc(11, 3, 6, 30)
+        );
+    }
+
+    #[test]
+    fn wall_clock_takes_the_first_repeated_time() {
+        // 00:30 EDT plus an hour is the first 01:30.
+        let x = utc(11, 3, 4, 30);
+        assert_eq!(
+            shift(x, TimeDelta::hours(1), ShiftMode::WallClock),
+            utc(11, 3, 5, 30)
+        );
+    }
+
+    #[test]
+    fn days_keep_the_local_time_across_transitions() {
+        // Noon EDT on November 2nd.
+        let x = utc(11, 2, 16, 0);
+        // Noon EST.
+        assert_eq!(
+            shift(x, TimeDelta::days(2), ShiftMode::WallClock),
+            utc(11, 4, 17, 0)
+        );
+        // 11:00 EST.
+        assert_eq!(
+            shift(x, TimeDelta::days(2), ShiftMode::Absolute),
+            utc(11, 4, 16, 0)
+        );
+    }
+
+    #[test]
+    fn skipped_times_are_measured_as_absolute() {
+        // 01:30 EST on March 10th, the clocks jump from 02:00 to 03:00.
+        let x = utc(3, 10, 6, 30);
+        // 02:30 doesn't exist, both give 03:30 EDT.
+        assert_eq!(
+            shift(x, TimeDelta::hours(1), ShiftMode::WallClock),
+            utc(3, 10, 7, 30)
+        );
+        assert_eq!(
+            shift(x, TimeDelta::hours(1), ShiftMode::Absolute),
+            utc(3, 10, 7, 30)
+        );
+    }
+
+    #[test]
+    fn parses_modes() {
+        assert!(ShiftMode::parse("wallclock") == Ok(ShiftMode::WallClock));
+        assert!(ShiftMode::parse("absolute") == Ok(ShiftMode::Absolute));
+        assert!(ShiftMode::parse("local").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+// `cron_since(pattern, last_seen)` emits the occurrences after a checkpoint
+// up to now, the catch-up set of an incremental pipeline.
+
+use chrono::{Local, TimeDelta};
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::align::start_of_day;
+use crate::schedule::{
+    datetime_from_micros, parse_pattern_or_error, pattern_parameter, timezone_parameter,
+};
+use crate::stream::{emit_occurrences, OccurrenceBindData, OccurrenceInitData};
+
+pub(crate) struct SinceVTab;
+
+impl VTab for SinceVTab {
+    type InitData = OccurrenceInitData;
+    type BindData = OccurrenceBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut OccurrenceBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+        (*data).init();
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        // Now is read once, when the query is bound.
+        let now = Local::now().with_timezone(&timezone);
+
+        // Occurrences fall on whole seconds, so the first one after the last
+        // one seen is at least a second later. A pipeline without a
+        // checkpoint catches up from the start of today.
+        let last_seen = bind.get_parameter(1);
+        (*data).start = match last_seen.to_string().as_str() {
+            "NULL" => start_of_day(&now.timezone(), now.date_naive()).unwrap_or(now),
+            _ => {
+                datetime_from_micros(last_seen.to_int64_timestamp(), &timezone)
+                    .ok_or("Invalid last seen time")?
+                    + TimeDelta::seconds(1)
+            }
+        };
+        (*data).until = now;
+
+        (*data).set_pattern(cron);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OccurrenceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).init();
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OccurrenceInitData>();
+        let bind_info = func.get_bind_data::<OccurrenceBindData>();
+        emit_occurrences(bind_info, init_info, output)
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
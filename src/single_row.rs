@@ -0,0 +1,83 @@
+// Plumbing for table functions that compute a single value at bind time and
+// return it as one row, standing in for scalar functions.
+
+use duckdb::vtab::{DataChunk, FlatVector, Free, Inserter};
+use std::ptr::null_mut;
+
+#[repr(C)]
+pub(crate) struct OnceInitData {
+    pub(crate) done: bool,
+}
+
+impl Free for OnceInitData {}
+
+// Emits a single row holding `value`, or NULL, on the first call and no rows
+// afterwards.
+pub(crate) fn emit_once<T: Copy>(
+    init: *mut OnceInitData,
+    output: &mut DataChunk,
+    value: Option<T>,
+) {
+    unsafe {
+        if (*init).done {
+            output.set_len(0);
+            return;
+        }
+        let mut vector: FlatVector = output.flat_vector(0);
+        match value {
+            Some(value) => vector.as_mut_slice::<T>()[0] = value,
+            None => vector.set_null(0),
+        }
+        output.set_len(1);
+        (*init).done = true;
+    }
+}
+
+// Bind data for single row functions returning a VARCHAR, a null pointer
+// represents NULL.
+#[repr(C)]
+pub(crate) struct TextBindData {
+    pub(crate) text: *mut String,
+}
+
+impl TextBindData {
+    pub(crate) fn set(&mut self, text: Option<String>) {
+        self.text = match text {
+            Some(text) => Box::into_raw(Box::new(text)),
+            None => null_mut(),
+        };
+    }
+}
+
+impl Free for TextBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.text.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.text));
+        }
+    }
+}
+
+// Emits a single VARCHAR row on the first call and no rows afterwards.
+pub(crate) fn emit_text_once(
+    init: *mut OnceInitData,
+    output: &mut DataChunk,
+    bind: *const TextBindData,
+) {
+    unsafe {
+        if (*init).done {
+            output.set_len(0);
+            return;
+        }
+        let mut vector: FlatVector = output.flat_vector(0);
+        if (*bind).text.is_null() {
+            vector.set_null(0);
+        } else {
+            vector.insert(0, (*(*bind).text).as_str());
+        }
+        output.set_len(1);
+        (*init).done = true;
+    }
+}
@@ -0,0 +1,168 @@
+// `cron_next_n_across_window(pattern, start, until, n)` picks `n` occurrences
+// spread evenly across a window, for thumbnails of long schedules that don't
+// pull every row.
+
+use chrono::{DateTime, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, pattern_parameter,
+    search_limit, timezone_parameter,
+};
+
+#[repr(C)]
+pub(crate) struct SpreadBindData {
+    picks: *mut Vec<i64>,
+}
+
+impl Free for SpreadBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.picks.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.picks));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct SpreadInitData {
+    // The index of the next pick to emit.
+    cursor: usize,
+}
+
+impl Free for SpreadInitData {}
+
+// Picks `n` of the occurrences within `[start, until]`, the first and the last
+// among them and the others at evenly spaced positions in between. Every
+// occurrence is returned when there are no more than `n`.
+//
+// The occurrences are counted in a first pass and the picks collected in a
+// second, so only the picks are held in memory.
+pub(crate) fn spread(
+    cron: &Cron,
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    n: usize,
+) -> Vec<DateTime<Tz>> {
+    let until = until + TimeDelta::seconds(1);
+    let total = occurrences_between(cron, start, until).count();
+    if n == 0 || total == 0 {
+        return Vec::new();
+    }
+    let positions: Vec<usize> = if n >= total {
+        (0..total).collect()
+    } else if n == 1 {
+        vec![0]
+    } else {
+        // Rounded to the nearest position, which are distinct since the
+        // step is at least one.
+        (0..n)
+            .map(|k| (k * (total - 1) + (n - 1) / 2) / (n - 1))
+            .collect()
+    };
+
+    let mut positions = positions.into_iter().peekable();
+    let mut picks = Vec::new();
+    for (i, x) in occurrences_between(cron, start, until).enumerate() {
+        match positions.peek() {
+            Some(&position) if position == i => {
+                picks.push(x);
+                positions.next();
+            }
+            Some(_) => {}
+            None => break,
+        }
+    }
+    picks
+}
+
+pub(crate) struct SpreadVTab;
+
+impl VTab for SpreadVTab {
+    type InitData = SpreadInitData;
+    type BindData = SpreadBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut SpreadBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).picks = null_mut();
+        bind.add_result_column("cron", LogicalType::new(LogicalTypeId::TimestampS));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        // Every occurrence is generated twice.
+        if until > search_limit(start) {
+            return Err(
+                "The range of cron_next_n_across_window may span at most five years".into(),
+            );
+        }
+        let n = bind.get_parameter(3).to_int64();
+        let n = usize::try_from(n).map_err(|_| {
+            format!(
+                "The number of occurrences must not be negative, found {}",
+                n
+            )
+        })?;
+
+        let picks = spread(&cron, start, until, n)
+            .iter()
+            .map(|x| x.timestamp())
+            .collect();
+        (*data).picks = Box::into_raw(Box::new(picks));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut SpreadInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).cursor = 0;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<SpreadInitData>();
+        let bind_info = func.get_bind_data::<SpreadBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let picks = &*(*bind_info).picks;
+        let first = (*init_info).cursor.min(picks.len());
+        let chunk = &picks[first..(first + max_items).min(picks.len())];
+        output.flat_vector(0).copy(chunk);
+        output.set_len(chunk.len());
+        (*init_info).cursor = first + chunk.len();
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Bigint),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
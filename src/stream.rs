@@ -0,0 +1,93 @@
+// Plumbing for table functions that emit the occurrences of a pattern between
+// two inclusive bounds, one vector sized chunk at a time.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{DataChunk, Free};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+#[repr(C)]
+pub(crate) struct OccurrenceBindData {
+    pub(crate) pattern: *mut Cron,
+    // The first instant that may be emitted.
+    pub(crate) start: DateTime<Tz>,
+    // The last instant that may be emitted.
+    pub(crate) until: DateTime<Tz>,
+}
+
+impl OccurrenceBindData {
+    // Must be called before anything can fail in bind so free() is safe.
+    pub(crate) fn init(&mut self) {
+        self.pattern = null_mut();
+    }
+
+    pub(crate) fn set_pattern(&mut self, pattern: Cron) {
+        self.pattern = Box::into_raw(Box::new(pattern));
+    }
+}
+
+impl Free for OccurrenceBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.pattern.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.pattern));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct OccurrenceInitData {
+    done: bool,
+    // The last occurrence emitted, generation resumes strictly after it.
+    cursor: Option<DateTime<Tz>>,
+}
+
+impl OccurrenceInitData {
+    pub(crate) fn init(&mut self) {
+        self.done = false;
+        self.cursor = None;
+    }
+}
+
+impl Free for OccurrenceInitData {}
+
+// Writes the next chunk of occurrences as seconds since the epoch into the
+// first column of `output`.
+pub(crate) fn emit_occurrences(
+    bind: *const OccurrenceBindData,
+    init: *mut OccurrenceInitData,
+    output: &mut DataChunk,
+) -> Result<(), Box<dyn std::error::Error>> {
+    unsafe {
+        if (*init).done {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let pattern = &*(*bind).pattern;
+        let until = (*bind).until;
+        let occurrences: Box<dyn Iterator<Item = DateTime<Tz>>> = match (*init).cursor {
+            Some(cursor) => Box::new(pattern.iter_after(cursor)),
+            None => Box::new(pattern.iter_from((*bind).start)),
+        };
+
+        let timestamps: Vec<DateTime<Tz>> = occurrences
+            .take_while(|&x| x <= until)
+            .take(max_items)
+            .collect();
+
+        if let Some(&last) = timestamps.last() {
+            (*init).cursor = Some(last);
+        }
+        let timestamps: Vec<i64> = timestamps.iter().map(|x| x.timestamp()).collect();
+        output.flat_vector(0).copy(&timestamps);
+        output.set_len(timestamps.len());
+        (*init).done = timestamps.len() < max_items;
+    }
+    Ok(())
+}
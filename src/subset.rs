@@ -0,0 +1,98 @@
+// `cron_is_subset(a, b)` checks that schedule `a` only fires at instants when
+// schedule `b` also fires.
+
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    parse_pattern_or_error, pattern_parameter, sample_window, timezone_parameter,
+};
+use crate::single_row::{emit_once, OnceInitData};
+
+// Dense patterns would take too long to check over the whole sample window,
+// so at most this many occurrences of `a` are checked.
+const MAX_CHECKED_OCCURRENCES: usize = 1_000_000;
+
+#[repr(C)]
+pub(crate) struct SubsetBindData {
+    // None when the check was cut short without finding a counterexample.
+    is_subset: Option<bool>,
+}
+
+impl Free for SubsetBindData {}
+
+// Checks every occurrence of `a` in the sample window against `b`.
+//
+// Returns None when `a` fires more than `MAX_CHECKED_OCCURRENCES` times in
+// the window and none of those checked was a counterexample, since the rest
+// of the window was never checked.
+pub(crate) fn is_subset(a: &Cron, b: &Cron, timezone: &Tz) -> Result<Option<bool>, String> {
+    let (start, end) = sample_window(timezone)?;
+    let mut checked = 0;
+    for x in a.iter_from(start).take_while(|&x| x < end) {
+        if checked == MAX_CHECKED_OCCURRENCES {
+            return Ok(None);
+        }
+        if !b.is_time_matching(&x).map_err(|err| err.to_string())? {
+            return Ok(Some(false));
+        }
+        checked += 1;
+    }
+    Ok(Some(true))
+}
+
+pub(crate) struct SubsetVTab;
+
+impl VTab for SubsetVTab {
+    type InitData = OnceInitData;
+    type BindData = SubsetBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut SubsetBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("is_subset", LogicalType::new(LogicalTypeId::Boolean));
+
+        let a = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let b = parse_pattern_or_error(&pattern_parameter(bind, 1)?)?;
+        let timezone = timezone_parameter(bind)?;
+
+        (*data).is_subset = is_subset(&a, &b, &timezone)?;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<SubsetBindData>();
+        emit_once(init_info, output, (*bind_info).is_subset);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Varchar),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
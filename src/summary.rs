@@ -0,0 +1,177 @@
+// `cron_describe_schedule_table(pattern, start, until)` summarizes a schedule
+// within a window in a single row, the schedule summary card of dashboards.
+
+use chrono::{DateTime, NaiveDate, TimeDelta};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, FlatVector, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_interval;
+
+use crate::age::to_interval;
+use crate::schedule::{
+    datetime_from_micros, occurrences_between, parse_pattern_or_error, pattern_parameter,
+    search_limit, timezone_parameter,
+};
+use crate::single_row::OnceInitData;
+
+// The aggregates of a window's occurrences. Everything but the number of
+// fires is None without occurrences, and the gaps without two of them.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Summary {
+    fires: i64,
+    first_fire: Option<i64>,
+    last_fire: Option<i64>,
+    min_gap: Option<duckdb_interval>,
+    median_gap: Option<duckdb_interval>,
+    max_gap: Option<duckdb_interval>,
+    // Days since the epoch, the earliest of the days tied for the most fires.
+    busiest_day: Option<i32>,
+    busiest_day_fires: Option<i64>,
+}
+
+impl Free for Summary {}
+
+// Computes the summary in a single pass over the occurrences. They come in
+// order, so the fires of a local day are consecutive.
+pub(crate) fn summarize(cron: &Cron, start: DateTime<Tz>, until: DateTime<Tz>) -> Summary {
+    let mut summary = Summary::default();
+    let mut gaps: Vec<TimeDelta> = Vec::new();
+    let mut previous: Option<DateTime<Tz>> = None;
+    let mut day: Option<(NaiveDate, i64)> = None;
+    let mut busiest: Option<(NaiveDate, i64)> = None;
+
+    for x in occurrences_between(cron, start, until) {
+        summary.fires += 1;
+        if let Some(previous) = previous {
+            gaps.push(x - previous);
+        }
+        previous = Some(x);
+
+        let date = x.date_naive();
+        let fires = match day {
+            Some((current, fires)) if current == date => fires + 1,
+            _ => 1,
+        };
+        day = Some((date, fires));
+        if !busiest.is_some_and(|(_, most)| fires <= most) {
+            busiest = Some((date, fires));
+        }
+        if summary.first_fire.is_none() {
+            summary.first_fire = Some(x.timestamp());
+        }
+    }
+    summary.last_fire = previous.map(|x| x.timestamp());
+
+    gaps.sort_unstable();
+    if !gaps.is_empty() {
+        let middle = gaps.len() / 2;
+        let median = if gaps.len() % 2 == 0 {
+            (gaps[middle - 1] + gaps[middle]) / 2
+        } else {
+            gaps[middle]
+        };
+        summary.min_gap = Some(to_interval(gaps[0]));
+        summary.median_gap = Some(to_interval(median));
+        summary.max_gap = Some(to_interval(gaps[gaps.len() - 1]));
+    }
+
+    if let Some((date, fires)) = busiest {
+        let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+        summary.busiest_day = Some((date - epoch).num_days() as i32);
+        summary.busiest_day_fires = Some(fires);
+    }
+    summary
+}
+
+// Writes a single value, or NULL, into the first row of a vector.
+fn write_value<T: Copy>(mut vector: FlatVector, value: Option<T>) {
+    match value {
+        Some(value) => vector.as_mut_slice::<T>()[0] = value,
+        None => vector.set_null(0),
+    }
+}
+
+pub(crate) struct SummaryVTab;
+
+impl VTab for SummaryVTab {
+    type InitData = OnceInitData;
+    type BindData = Summary;
+
+    unsafe fn bind(bind: &BindInfo, data: *mut Summary) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("fires", LogicalType::new(LogicalTypeId::Bigint));
+        bind.add_result_column("first_fire", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("last_fire", LogicalType::new(LogicalTypeId::TimestampS));
+        bind.add_result_column("min_gap", LogicalType::new(LogicalTypeId::Interval));
+        bind.add_result_column("median_gap", LogicalType::new(LogicalTypeId::Interval));
+        bind.add_result_column("max_gap", LogicalType::new(LogicalTypeId::Interval));
+        bind.add_result_column("busiest_day", LogicalType::new(LogicalTypeId::Date));
+        bind.add_result_column("busiest_day_fires", LogicalType::new(LogicalTypeId::Bigint));
+        *data = Summary::default();
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let timezone = timezone_parameter(bind)?;
+        let start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        let until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        // Every gap is held in memory to find the median.
+        if until > search_limit(start) {
+            return Err(
+                "The range of cron_describe_schedule_table may span at most five years".into(),
+            );
+        }
+
+        *data = summarize(&cron, start, until);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let summary = *func.get_bind_data::<Summary>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        write_value(output.flat_vector(0), Some(summary.fires));
+        write_value(output.flat_vector(1), summary.first_fire);
+        write_value(output.flat_vector(2), summary.last_fire);
+        write_value(output.flat_vector(3), summary.min_gap);
+        write_value(output.flat_vector(4), summary.median_gap);
+        write_value(output.flat_vector(5), summary.max_gap);
+        write_value(output.flat_vector(6), summary.busiest_day);
+        write_value(output.flat_vector(7), summary.busiest_day_fires);
+        output.set_len(1);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::new(LogicalTypeId::Varchar),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,180 @@
+// `cron_to_systemd_oncalendar(pattern)` converts a pattern into the
+// `OnCalendar=` syntax of systemd timers.
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::fields::{parse_fields, Field};
+use crate::schedule::{parse_pattern_or_error, pattern_parameter};
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+const SYSTEMD_WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+// Joins sorted values into a list of ranges like `a..b`, with `separator`
+// between the bounds, writing each value with `format`.
+pub(crate) fn ranges(values: &[u32], separator: &str, format: impl Fn(u32) -> String) -> String {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < values.len() {
+        let mut j = i;
+        while j + 1 < values.len() && values[j + 1] == values[j] + 1 {
+            j += 1;
+        }
+        if j > i {
+            items.push(format!(
+                "{}{}{}",
+                format(values[i]),
+                separator,
+                format(values[j])
+            ));
+        } else {
+            items.push(format(values[i]));
+        }
+        i = j + 1;
+    }
+    items.join(",")
+}
+
+// Writes a numeric field, None if it uses items systemd can't express.
+fn numeric(field: &Field) -> Option<String> {
+    if !field.specials.is_empty() {
+        return None;
+    }
+    if field.wildcard {
+        return Some("*".to_string());
+    }
+    Some(ranges(&field.values, "..", |x| format!("{:02}", x)))
+}
+
+// Converts a pattern into an `OnCalendar=` expression, e.g. `0 9 * * 1-5`
+// into `Mon..Fri *-*-* 09:00:00`. Both match the weekday and the date, so a
+// pattern restricting both converts faithfully.
+//
+// Returns None for patterns using `L`, `W` or `#`, which have no clean
+// systemd equivalent.
+pub(crate) fn to_oncalendar(pattern: &str) -> Result<Option<String>, String> {
+    parse_pattern_or_error(pattern)?;
+    let fields = parse_fields(pattern)?;
+    // Seconds, minutes, hours, days and months.
+    let parts = match fields[..5]
+        .iter()
+        .map(numeric)
+        .collect::<Option<Vec<String>>>()
+    {
+        Some(parts) => parts,
+        None => return Ok(None),
+    };
+
+    let weekdays = &fields[5];
+    if !weekdays.specials.is_empty() {
+        return Ok(None);
+    }
+    let mut calendar = String::new();
+    if !weekdays.wildcard && weekdays.values.len() < 7 {
+        // systemd weeks run from Monday to Sunday.
+        let mut values: Vec<u32> = weekdays
+            .values
+            .iter()
+            .map(|&x| if x == 0 { 7 } else { x })
+            .collect();
+        values.sort_unstable();
+        calendar.push_str(&ranges(&values, "..", |x| {
+            SYSTEMD_WEEKDAYS[x as usize - 1].to_string()
+        }));
+        calendar.push(' ');
+    }
+    calendar.push_str(&format!(
+        "*-{}-{} {}:{}:{}",
+        parts[4], parts[3], parts[2], parts[1], parts[0]
+    ));
+    Ok(Some(calendar))
+}
+
+pub(crate) struct OnCalendarVTab;
+
+impl VTab for OnCalendarVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("oncalendar", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(None);
+
+        let pattern = pattern_parameter(bind, 0)?;
+        (*data).set(to_oncalendar(&pattern)?);
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oncalendar(pattern: &str) -> Option<String> {
+        to_oncalendar(pattern).unwrap()
+    }
+
+    #[test]
+    fn converts_patterns() {
+        assert_eq!(
+            oncalendar("0 9 * * 1-5").as_deref(),
+            Some("Mon..Fri *-*-* 09:00:00")
+        );
+        assert_eq!(oncalendar("30 2 1 * *").as_deref(), Some("*-*-01 02:30:00"));
+        assert_eq!(
+            oncalendar("*/15 * * * *").as_deref(),
+            Some("*-*-* *:00,15,30,45:00")
+        );
+        assert_eq!(
+            oncalendar("0 12 1 1-3,6 *").as_deref(),
+            Some("*-01..03,06-01 12:00:00")
+        );
+        assert_eq!(
+            oncalendar("15 0 0 * * *").as_deref(),
+            Some("*-*-* 00:00:15")
+        );
+    }
+
+    #[test]
+    fn weeks_run_from_monday() {
+        assert_eq!(
+            oncalendar("0 0 * * 0,6").as_deref(),
+            Some("Sat..Sun *-*-* 00:00:00")
+        );
+        assert_eq!(
+            oncalendar("0 0 * * 0,1").as_deref(),
+            Some("Mon,Sun *-*-* 00:00:00")
+        );
+    }
+
+    #[test]
+    fn special_items_have_no_equivalent() {
+        assert_eq!(oncalendar("0 0 L * *"), None);
+        assert_eq!(oncalendar("0 0 15W * *"), None);
+        assert_eq!(oncalendar("0 0 * * 5#2"), None);
+    }
+}
@@ -0,0 +1,199 @@
+// `cron_timeline(schedules, start, until)` interleaves the occurrences of
+// several named schedules into one labeled stream.
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::params::{parse_list, parse_struct};
+use crate::schedule::{datetime_from_micros, merge_occurrences, parse_pattern, timezone_parameter};
+
+#[repr(C)]
+pub(crate) struct TimelineBindData {
+    names: *mut Vec<String>,
+    patterns: *mut Vec<Cron>,
+    start: DateTime<Tz>,
+    // Exclusive.
+    until: DateTime<Tz>,
+}
+
+impl Free for TimelineBindData {
+    fn free(&mut self) {
+        unsafe {
+            if !self.names.is_null() {
+                drop(Box::from_raw(self.names));
+            }
+            if !self.patterns.is_null() {
+                drop(Box::from_raw(self.patterns));
+            }
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct TimelineInitData {
+    done: bool,
+    // The last row emitted as the index of its schedule and its instant.
+    // Coincident occurrences are emitted in schedule order, so generation
+    // resumes after it for that schedule and the ones before it, and at it
+    // for the ones after.
+    cursor: Option<(usize, DateTime<Tz>)>,
+}
+
+impl TimelineInitData {
+    pub(crate) fn init(&mut self) {
+        self.done = false;
+        self.cursor = None;
+    }
+}
+
+impl Free for TimelineInitData {}
+
+// Generates the next chunk of the merged occurrences of `patterns` within
+// `[start, until)`, each tagged with the index of its pattern.
+pub(crate) unsafe fn next_tagged_chunk(
+    patterns: &[Cron],
+    start: DateTime<Tz>,
+    until: DateTime<Tz>,
+    init: *mut TimelineInitData,
+    max_items: usize,
+) -> Vec<(usize, DateTime<Tz>)> {
+    if (*init).done {
+        return Vec::new();
+    }
+    let streams = patterns
+        .iter()
+        .enumerate()
+        .map(|(i, pattern)| -> Box<dyn Iterator<Item = DateTime<Tz>>> {
+            match (*init).cursor {
+                Some((last, at)) if i <= last => Box::new(pattern.iter_after(at)),
+                Some((_, at)) => Box::new(pattern.iter_from(at)),
+                None => Box::new(pattern.iter_from(start)),
+            }
+        })
+        .collect();
+
+    let rows: Vec<(usize, DateTime<Tz>)> = merge_occurrences(streams)
+        .take_while(|&(_, x)| x < until)
+        .take(max_items)
+        .collect();
+
+    if let Some(&last) = rows.last() {
+        (*init).cursor = Some(last);
+    }
+    (*init).done = rows.len() < max_items;
+    rows
+}
+
+// Parses DuckDB's rendering of a LIST(STRUCT(name VARCHAR, pattern VARCHAR))
+// into the names and parsed patterns of the schedules.
+fn parse_schedules(text: &str) -> Result<(Vec<String>, Vec<Cron>), String> {
+    let mut names = Vec::new();
+    let mut patterns = Vec::new();
+    for schedule in parse_list(text)? {
+        let mut name = None;
+        let mut pattern = None;
+        for (field, value) in parse_struct(&schedule)? {
+            match field.as_str() {
+                "name" => name = Some(value),
+                "pattern" => pattern = Some(value),
+                _ => return Err(format!("Unknown schedule field: {}", field)),
+            }
+        }
+        let name = name.ok_or("Every schedule needs a name")?;
+        let pattern = pattern.ok_or_else(|| format!("Schedule '{}' has no pattern", name))?;
+        patterns.push(parse_pattern(&pattern).map_err(|err| {
+            format!(
+                "Failed to parse cron expression of schedule '{}': {}",
+                name, err
+            )
+        })?);
+        names.push(name);
+    }
+    Ok((names, patterns))
+}
+
+pub(crate) struct TimelineVTab;
+
+impl VTab for TimelineVTab {
+    type InitData = TimelineInitData;
+    type BindData = TimelineBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TimelineBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).names = null_mut();
+        (*data).patterns = null_mut();
+        bind.add_result_column("name", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("fire", LogicalType::new(LogicalTypeId::TimestampS));
+
+        let (names, patterns) = parse_schedules(&bind.get_parameter(0).to_string())?;
+        let timezone = timezone_parameter(bind)?;
+        (*data).start = datetime_from_micros(bind.get_parameter(1).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid starting time")?;
+        (*data).until = datetime_from_micros(bind.get_parameter(2).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid until time")?;
+        (*data).names = Box::into_raw(Box::new(names));
+        (*data).patterns = Box::into_raw(Box::new(patterns));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut TimelineInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).init();
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<TimelineInitData>();
+        let bind_info = func.get_bind_data::<TimelineBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let names = &*(*bind_info).names;
+        let rows = next_tagged_chunk(
+            &*(*bind_info).patterns,
+            (*bind_info).start,
+            (*bind_info).until,
+            init_info,
+            max_items,
+        );
+
+        let mut labels = output.flat_vector(0);
+        for (row, &(i, _)) in rows.iter().enumerate() {
+            labels.insert(row, names[i].as_str());
+        }
+        let fires: Vec<i64> = rows.iter().map(|(_, x)| x.timestamp()).collect();
+        output.flat_vector(1).copy(&fires);
+        output.set_len(rows.len());
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![
+            LogicalType::list(&LogicalType::struct_type(&[
+                ("name", LogicalType::new(LogicalTypeId::Varchar)),
+                ("pattern", LogicalType::new(LogicalTypeId::Varchar)),
+            ])),
+            LogicalType::new(LogicalTypeId::Timestamp),
+            LogicalType::new(LogicalTypeId::Timestamp),
+        ])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
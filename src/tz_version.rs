@@ -0,0 +1,49 @@
+// `cron_tz_version()` reports the release of the IANA time zone database the
+// extension evaluates zones with, to audit which rules are in effect.
+//
+// Zone rules are compiled in by chrono-tz rather than read from the operating
+// system, so they only change with the extension. chrono-tz has no way to
+// load another database at runtime, which is why no function takes a
+// `timezone_database` parameter to select the system one instead.
+
+use duckdb::vtab::{BindInfo, DataChunk, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab};
+
+use crate::single_row::{emit_text_once, OnceInitData, TextBindData};
+
+// The release bundled with chrono-tz 0.9.0, pinned in Cargo.toml. chrono-tz
+// doesn't expose it, so it must be updated along with the dependency.
+const BUNDLED_TZDB_VERSION: &str = "2024a";
+
+pub(crate) struct TzVersionVTab;
+
+impl VTab for TzVersionVTab {
+    type InitData = OnceInitData;
+    type BindData = TextBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut TextBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("version", LogicalType::new(LogicalTypeId::Varchar));
+        (*data).set(Some(BUNDLED_TZDB_VERSION.to_string()));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<TextBindData>();
+        emit_text_once(init_info, output, bind_info);
+        Ok(())
+    }
+}
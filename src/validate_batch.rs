@@ -0,0 +1,120 @@
+// `cron_validate_batch(patterns)` checks a whole list of patterns at once,
+// returning a row per pattern instead of failing on the first bad one.
+
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+use libduckdb_sys::duckdb_vector_size;
+use std::ptr::null_mut;
+
+use crate::params::parse_list;
+use crate::schedule::{check_pattern_text, parse_pattern_or_error};
+
+#[repr(C)]
+pub(crate) struct ValidateBatchBindData {
+    // Each pattern, None when NULL, and the error it failed with, if any.
+    results: *mut Vec<(Option<String>, Option<String>)>,
+}
+
+impl Free for ValidateBatchBindData {
+    fn free(&mut self) {
+        unsafe {
+            if self.results.is_null() {
+                return;
+            }
+            drop(Box::from_raw(self.results));
+        }
+    }
+}
+
+#[repr(C)]
+pub(crate) struct ValidateBatchInitData {
+    // The index of the next result to emit.
+    pub(crate) cursor: usize,
+}
+
+impl Free for ValidateBatchInitData {}
+
+// Validates a pattern the way the other functions parse it.
+fn validate(pattern: &str) -> Option<String> {
+    check_pattern_text(pattern)
+        .and_then(|_| parse_pattern_or_error(pattern).map(|_| ()))
+        .err()
+}
+
+pub(crate) struct ValidateBatchVTab;
+
+impl VTab for ValidateBatchVTab {
+    type InitData = ValidateBatchInitData;
+    type BindData = ValidateBatchBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut ValidateBatchBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).results = null_mut();
+        bind.add_result_column("pattern", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("valid", LogicalType::new(LogicalTypeId::Boolean));
+        bind.add_result_column("error", LogicalType::new(LogicalTypeId::Varchar));
+
+        let results = parse_list(&bind.get_parameter(0).to_string())?
+            .into_iter()
+            .map(|pattern| {
+                let error = validate(&pattern);
+                let pattern = if pattern == "NULL" {
+                    None
+                } else {
+                    Some(pattern)
+                };
+                (pattern, error)
+            })
+            .collect();
+        (*data).results = Box::into_raw(Box::new(results));
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut ValidateBatchInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).cursor = 0;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<ValidateBatchInitData>();
+        let bind_info = func.get_bind_data::<ValidateBatchBindData>();
+
+        let max_items: usize = duckdb_vector_size().try_into()?;
+        let results = &*(*bind_info).results;
+        let start = (*init_info).cursor.min(results.len());
+        let chunk = &results[start..(start + max_items).min(results.len())];
+
+        let mut patterns = output.flat_vector(0);
+        let mut valid = output.flat_vector(1);
+        let mut errors = output.flat_vector(2);
+        for (row, (pattern, error)) in chunk.iter().enumerate() {
+            match pattern {
+                Some(pattern) => patterns.insert(row, pattern.as_str()),
+                None => patterns.set_null(row),
+            }
+            valid.as_mut_slice::<bool>()[row] = error.is_none();
+            match error {
+                Some(error) => errors.insert(row, error.as_str()),
+                None => errors.set_null(row),
+            }
+        }
+        output.set_len(chunk.len());
+        (*init_info).cursor = start + chunk.len();
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::list(&LogicalType::new(
+            LogicalTypeId::Varchar,
+        ))])
+    }
+}
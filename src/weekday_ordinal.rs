@@ -0,0 +1,104 @@
+// `cron_weekday_ordinal(ts)` classifies a date as the nth weekday of its
+// month, e.g. the 2nd Tuesday, to check what `#` and `L` patterns select.
+
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, Inserter, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{datetime_from_micros, timezone_parameter};
+use crate::single_row::OnceInitData;
+
+#[repr(C)]
+pub(crate) struct WeekdayOrdinalBindData {
+    weekday: Weekday,
+    ordinal: i32,
+    is_last: bool,
+}
+
+impl Free for WeekdayOrdinalBindData {}
+
+// Which occurrence of its weekday a date is within its month, counting from
+// one, and whether it is the last one.
+pub(crate) fn weekday_ordinal(date: NaiveDate) -> (Weekday, i32, bool) {
+    let ordinal = (date.day0() / 7 + 1) as i32;
+    let is_last = (date + TimeDelta::days(7)).month() != date.month();
+    (date.weekday(), ordinal, is_last)
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+pub(crate) struct WeekdayOrdinalVTab;
+
+impl VTab for WeekdayOrdinalVTab {
+    type InitData = OnceInitData;
+    type BindData = WeekdayOrdinalBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut WeekdayOrdinalBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("weekday", LogicalType::new(LogicalTypeId::Varchar));
+        bind.add_result_column("ordinal", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("is_last", LogicalType::new(LogicalTypeId::Boolean));
+
+        let timezone = timezone_parameter(bind)?;
+        let at = datetime_from_micros(bind.get_parameter(0).to_int64_timestamp(), &timezone)
+            .ok_or("Invalid timestamp")?;
+        let (weekday, ordinal, is_last) = weekday_ordinal(at.date_naive());
+        (*data).weekday = weekday;
+        (*data).ordinal = ordinal;
+        (*data).is_last = is_last;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<WeekdayOrdinalBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+        output
+            .flat_vector(0)
+            .insert(0, weekday_name((*bind_info).weekday));
+        output.flat_vector(1).as_mut_slice::<i32>()[0] = (*bind_info).ordinal;
+        output.flat_vector(2).as_mut_slice::<bool>()[0] = (*bind_info).is_last;
+        output.set_len(1);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Timestamp)])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![(
+            "timezone".to_string(),
+            LogicalType::new(LogicalTypeId::Varchar),
+        )])
+    }
+}
@@ -0,0 +1,149 @@
+// `cron_weekly_pattern(pattern)` summarizes in which weekday and hour cells
+// of a week a pattern can fire, for drawing schedule heatmaps.
+
+use chrono::{Datelike, TimeDelta, Timelike};
+use chrono_tz::Tz;
+use croner::Cron;
+use duckdb::vtab::{
+    BindInfo, DataChunk, Free, FunctionInfo, InitInfo, LogicalType, LogicalTypeId, VTab,
+};
+
+use crate::schedule::{
+    parse_pattern_or_error, pattern_parameter, sample_window, timezone_parameter,
+};
+use crate::single_row::OnceInitData;
+
+#[repr(C)]
+pub(crate) struct WeeklyBindData {
+    // Indexed by weekday, relative to the configured week start, then hour.
+    grid: [[bool; 24]; 7],
+}
+
+impl Free for WeeklyBindData {}
+
+// Parses the week start, returning how many days Sunday is offset from the
+// first column.
+pub(crate) fn week_start_offset(week_start: &str) -> Result<u32, String> {
+    match week_start.to_lowercase().as_str() {
+        "sunday" => Ok(0),
+        "monday" => Ok(6),
+        _ => Err(format!(
+            "Invalid week start '{}', expected 'sunday' or 'monday'",
+            week_start
+        )),
+    }
+}
+
+// Resolves the first day of the week from the `week_starts_on` named
+// parameter. Weeks start on Monday by default, as in ISO 8601.
+pub(crate) fn week_start_parameter(bind: &BindInfo) -> Result<u32, String> {
+    match bind.get_named_parameter("week_starts_on") {
+        Some(value) => week_start_offset(&value.to_string()),
+        None => week_start_offset("monday"),
+    }
+}
+
+// Marks every weekday/hour cell in which the pattern fires during the sample.
+pub(crate) fn weekly_grid(
+    cron: &Cron,
+    timezone: &Tz,
+    sunday_offset: u32,
+) -> Result<[[bool; 24]; 7], String> {
+    let mut grid = [[false; 24]; 7];
+    // Weekday and day-of-month constraints interact, so a single week is not
+    // representative of the pattern.
+    let (mut from, end) = sample_window(timezone)?;
+
+    while let Ok(x) = cron.find_next_occurrence(&from, true) {
+        if x >= end {
+            break;
+        }
+        let weekday = (x.weekday().num_days_from_sunday() + sunday_offset) % 7;
+        grid[weekday as usize][x.hour() as usize] = true;
+
+        // Nothing more can be learned about this hour, skip to the next one.
+        let into_hour = i64::from(x.minute() * 60 + x.second());
+        from = x + TimeDelta::seconds(3600 - into_hour);
+    }
+    Ok(grid)
+}
+
+pub(crate) struct WeeklyVTab;
+
+impl VTab for WeeklyVTab {
+    type InitData = OnceInitData;
+    type BindData = WeeklyBindData;
+
+    unsafe fn bind(
+        bind: &BindInfo,
+        data: *mut WeeklyBindData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        bind.add_result_column("weekday", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("hour", LogicalType::new(LogicalTypeId::Integer));
+        bind.add_result_column("fires", LogicalType::new(LogicalTypeId::Boolean));
+
+        let cron = parse_pattern_or_error(&pattern_parameter(bind, 0)?)?;
+        let sunday_offset = week_start_parameter(bind)?;
+        let timezone = timezone_parameter(bind)?;
+
+        (*data).grid = weekly_grid(&cron, &timezone, sunday_offset)?;
+        Ok(())
+    }
+
+    unsafe fn init(
+        _: &InitInfo,
+        data: *mut OnceInitData,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        (*data).done = false;
+        Ok(())
+    }
+
+    unsafe fn func(
+        func: &FunctionInfo,
+        output: &mut DataChunk,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let init_info = func.get_init_data::<OnceInitData>();
+        let bind_info = func.get_bind_data::<WeeklyBindData>();
+
+        if (*init_info).done {
+            output.set_len(0);
+            return Ok(());
+        }
+
+        let mut weekdays = output.flat_vector(0);
+        let mut hours = output.flat_vector(1);
+        let mut fires = output.flat_vector(2);
+        let weekdays = weekdays.as_mut_slice::<i32>();
+        let hours = hours.as_mut_slice::<i32>();
+        let fires = fires.as_mut_slice::<bool>();
+
+        for (weekday, row) in (*bind_info).grid.iter().enumerate() {
+            for (hour, &fired) in row.iter().enumerate() {
+                let i = weekday * 24 + hour;
+                weekdays[i] = weekday as i32;
+                hours[i] = hour as i32;
+                fires[i] = fired;
+            }
+        }
+        output.set_len(7 * 24);
+        (*init_info).done = true;
+        Ok(())
+    }
+
+    fn parameters() -> Option<Vec<LogicalType>> {
+        Some(vec![LogicalType::new(LogicalTypeId::Varchar)])
+    }
+
+    fn named_parameters() -> Option<Vec<(String, LogicalType)>> {
+        Some(vec![
+            (
+                "timezone".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+            (
+                "week_starts_on".to_string(),
+                LogicalType::new(LogicalTypeId::Varchar),
+            ),
+        ])
+    }
+}